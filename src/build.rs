@@ -26,6 +26,11 @@ use crate::Ui;
 /// paws::build! {
 ///     group ui => ((800.0, 600.0), Layout::Freeform) { }
 ///     process element, &mut ui => (args) { }
+///     draw ui { }
+///     clip ui => ((800.0, 600.0), Layout::Freeform) { }
+///     if true { } else { }
+///     for _ in 0..3 { }
+///     match args { () => { } }
 ///     // or any old statement
 /// }
 /// ```
@@ -108,8 +113,154 @@ use crate::Ui;
 /// The first, probably most noticable difference, is that the method names are `begin` and `end`, instead of `push` and
 /// `pop`, as previously mentioned. The second difference is that `begin` receives the UI instance before all other
 /// parameters.
+///
+/// ## `draw`
+///
+/// `draw` wraps [`Ui::draw`]. The UI instance must be a plain identifier, as it's rebound inside of the closure passed
+/// to `draw`:
+/// ```ignore
+/// paws::build! {
+///     draw ui {
+///         ui.render().fill(rect, color, 0.0);
+///     }
+/// }
+/// // becomes
+/// ui.draw(|ui| {
+///     ui.render().fill(rect, color, 0.0);
+/// });
+/// ```
+///
+/// ## `clip`
+///
+/// `clip` pushes a group just like `group`, but also clips drawing to the group's rectangle using [`Ui::clip`]. The
+/// clip is undone once the group is popped:
+/// ```ignore
+/// paws::build! {
+///     clip ui => ((300.0, 300.0), Layout::Freeform) {
+///         println!("hi!");
+///     }
+/// }
+/// // becomes
+/// ui.push((300.0, 300.0), Layout::Freeform);
+/// ui.painter().push();
+/// ui.clip();
+/// {
+///     println!("hi!");
+/// }
+/// ui.painter().pop();
+/// ui.pop();
+/// ```
+///
+/// ## Control flow
+///
+/// `if` (along with `else if` and `else`), `for`, and `match` can be used as usual, except that their bodies are
+/// `build!` bodies themselves, so they can contain `group`s, `process`es, and so on. The arms of a `match` must use
+/// braces.
+///
+/// If the control flow is followed by a semicolon, it's treated as any old statement instead, and its bodies are
+/// plain Rust code:
+/// ```
+/// # let items = [1, 2, 3];
+/// paws::build! {
+///     for item in items.iter() { println!("{}", item) };
+///     match items.len() { 0 => println!("empty"), n => println!("{} items", n) };
+/// }
+/// ```
+///
+/// Here's how control flow can be combined with other `build!` forms:
+/// ```
+/// # use paws::{Ui, NoRenderer, Layout};
+/// # let mut ui = Ui::new(NoRenderer);
+/// # ui.root((800.0, 600.0), Layout::Vertical);
+/// let items = ["apple", "banana", "cherry"];
+/// let selected = Some(1);
+/// paws::build! {
+///     for (i, item) in items.iter().enumerate() {
+///         group ui => ((200.0, 20.0), Layout::Freeform) {
+///             if selected == Some(i) {
+///                 draw ui {
+///                     ui.fill(paws::Color::WHITE);
+///                 }
+///             } else if item.starts_with('c') {
+///                 println!("{} is a c-fruit", item);
+///             }
+///         }
+///     }
+///     match selected {
+///         Some(_) => {
+///             group ui => ((200.0, 20.0), Layout::Freeform) {}
+///         }
+///         None => {}
+///     }
+/// }
+/// assert_eq!(ui.cursor(), paws::vector(0.0, 80.0));
+/// ```
 #[macro_export]
 macro_rules! build {
+    // internal rules for control flow. the heads of `if`, `for`, and `match` are arbitrary expressions, which can't be
+    // followed by a block in macro patterns, so they're munched token by token until a block is found.
+    // control flow followed by a semicolon is a plain statement, whose bodies are passed through as is; `if` chains
+    // are thus accumulated both with (`$out`) and without (`$raw`) wrapping their bodies in `build!`
+
+    (@if [$($out:tt)*] [$($raw:tt)*] [$($head:tt)*] { $($body:tt)* } $($rest:tt)*) => {
+        $crate::build!(
+            @else
+            [$($out)* if $($head)* { $crate::build! { $($body)* } }]
+            [$($raw)* if $($head)* { $($body)* }]
+            $($rest)*
+        )
+    };
+    (@if [$($out:tt)*] [$($raw:tt)*] [$($head:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::build!(@if [$($out)*] [$($raw)*] [$($head)* $next] $($rest)*)
+    };
+    (@else [$($out:tt)*] [$($raw:tt)*] else if $($rest:tt)*) => {
+        $crate::build!(@if [$($out)* else] [$($raw)* else] [] $($rest)*)
+    };
+    (@else [$($out:tt)*] [$($raw:tt)*] else { $($body:tt)* } ; $($rest:tt)*) => {
+        $($raw)* else { $($body)* };
+        $crate::build!($($rest)*)
+    };
+    (@else [$($out:tt)*] [$($raw:tt)*] else { $($body:tt)* } $($rest:tt)*) => {
+        $($out)* else { $crate::build! { $($body)* } }
+        $crate::build!($($rest)*)
+    };
+    (@else [$($out:tt)*] [$($raw:tt)*] ; $($rest:tt)*) => {
+        $($raw)*;
+        $crate::build!($($rest)*)
+    };
+    (@else [$($out:tt)*] [$($raw:tt)*] $($rest:tt)*) => {
+        $($out)*
+        $crate::build!($($rest)*)
+    };
+    (@for [$($head:tt)*] { $($body:tt)* } ; $($rest:tt)*) => {
+        for $($head)* { $($body)* };
+        $crate::build!($($rest)*)
+    };
+    (@for [$($head:tt)*] { $($body:tt)* } $($rest:tt)*) => {
+        for $($head)* { $crate::build! { $($body)* } }
+        $crate::build!($($rest)*)
+    };
+    (@for [$($head:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::build!(@for [$($head)* $next] $($rest)*)
+    };
+    (@match [$($head:tt)*] { $($body:tt)* } ; $($rest:tt)*) => {
+        match $($head)* { $($body)* };
+        $crate::build!($($rest)*)
+    };
+    (
+        @match [$($head:tt)*]
+        { $( $($pattern:pat)|+ $(if $guard:expr)? => { $($arm:tt)* } $(,)? )* }
+        $($rest:tt)*
+    ) => {
+        match $($head)* {
+            $( $($pattern)|+ $(if $guard)? => { $crate::build! { $($arm)* } } )*
+        }
+        $crate::build!($($rest)*)
+    };
+    (@match [$($head:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::build!(@match [$($head)* $next] $($rest)*)
+    };
+
     ( group $ui:expr => $args:tt $then:tt $($rest:tt)* ) => {
         $ui.push $args;
         $crate::build! $then
@@ -122,6 +273,28 @@ macro_rules! build {
         $element.end();
         $crate::build!($($rest)*)
     };
+    ( draw $ui:ident $then:tt $($rest:tt)* ) => {
+        $ui.draw(|$ui| { $crate::build! $then });
+        $crate::build!($($rest)*)
+    };
+    ( clip $ui:expr => $args:tt $then:tt $($rest:tt)* ) => {
+        $ui.push $args;
+        $crate::Renderer::push(&mut $ui.painter());
+        $ui.clip();
+        $crate::build! $then
+        $crate::Renderer::pop(&mut $ui.painter());
+        $ui.pop();
+        $crate::build!($($rest)*)
+    };
+    ( if $($rest:tt)* ) => {
+        $crate::build!(@if [] [] [] $($rest)*)
+    };
+    ( for $($rest:tt)* ) => {
+        $crate::build!(@for [] $($rest)*)
+    };
+    ( match $($rest:tt)* ) => {
+        $crate::build!(@match [] $($rest)*)
+    };
     ( $statement:stmt ; $($rest:tt)* ) => {
         $statement
         $crate::build!($($rest)*)
@@ -131,7 +304,7 @@ macro_rules! build {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Ui, NoRenderer, Layout, Vector};
+    use crate::{Ui, NoRenderer, Layout, Vector, point, Color, DrawCommand, RecordingRenderer, Rect};
 
     #[test]
     fn empty() {
@@ -204,5 +377,83 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn draw_and_clip() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        build! {
+            clip ui => ((100.0, 50.0), Layout::Freeform) {
+                draw ui {
+                    ui.fill(Color::WHITE);
+                }
+            }
+        }
+        let rect = Rect::new(point(0.0, 0.0), (100.0, 50.0));
+        assert_eq!(
+            ui.renderer().commands(),
+            [
+                DrawCommand::Push,
+                DrawCommand::Clip(rect),
+                DrawCommand::Push,
+                DrawCommand::Translate(point(0.0, 0.0)),
+                DrawCommand::Fill {
+                    rect,
+                    color: Color::WHITE,
+                    radius: 0.0
+                },
+                DrawCommand::Pop,
+                DrawCommand::Pop,
+            ]
+        );
+        assert_eq!(ui.cursor(), point(0.0, 50.0));
+    }
+
+    #[test]
+    fn control_flow() {
+        let mut ui = Ui::new(NoRenderer);
+        ui.root((800.0, 600.0), Layout::Vertical);
+        let mut log = Vec::new();
+        build! {
+            for i in 0..4 {
+                if i == 0 {
+                    log.push("first");
+                } else if let 1 | 2 = i {
+                    group ui => ((10.0, 10.0), Layout::Freeform) {
+                        log.push("middle");
+                    }
+                } else {
+                    log.push("last");
+                }
+            }
+            match log.len() {
+                0 => {}
+                n if n > 2 => {
+                    group ui => ((10.0, 5.0), Layout::Freeform) {}
+                }
+                _ => {
+                    log.push("unreachable");
+                }
+            }
+            if log.is_empty() {
+                log.push("unreachable");
+            }
+            log.push("done");
+        }
+        assert_eq!(log, ["first", "middle", "middle", "last", "done"]);
+        assert_eq!(ui.cursor(), point(0.0, 25.0));
+    }
+
+    #[test]
+    fn control_flow_statements() {
+        let mut log = Vec::new();
+        build! {
+            for i in 0..2 { log.push(i) };
+            match log.len() { 2 => log.push(2), _ => log.push(0) };
+            if log.is_empty() { log.push(0) } else if log.len() > 5 { log.push(0) } else { log.push(3) };
+            if log.len() == 4 { log.push(4) };
+        }
+        assert_eq!(log, [0, 1, 2, 3, 4]);
+    }
 }
 
@@ -199,6 +199,34 @@ impl Rect {
         self.position.y + self.size.y / 2.0
     }
 
+    /// Returns whether the rectangle contains the given point. The left and top edges are inclusive, while the right
+    /// and bottom edges are exclusive.
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.left()
+            && point.x < self.right()
+            && point.y >= self.top()
+            && point.y < self.bottom()
+    }
+
+    /// Returns the intersection of this rectangle and the other rectangle. If they don't intersect, the resulting
+    /// rectangle is empty.
+    pub fn intersection(self, other: Rect) -> Self {
+        let left = f32::max(self.left(), other.left());
+        let top = f32::max(self.top(), other.top());
+        let right = f32::min(self.right(), other.right()).max(left);
+        let bottom = f32::min(self.bottom(), other.bottom()).max(top);
+        Self::new(point(left, top), vector(right - left, bottom - top))
+    }
+
+    /// Returns the smallest rectangle that contains both this rectangle and the other rectangle.
+    pub fn union(self, other: Rect) -> Self {
+        let left = f32::min(self.left(), other.left());
+        let top = f32::min(self.top(), other.top());
+        let right = f32::max(self.right(), other.right());
+        let bottom = f32::max(self.bottom(), other.bottom());
+        Self::new(point(left, top), vector(right - left, bottom - top))
+    }
+
     /// Returns a _sorted_ rectangle - that is, the same rectangle, but with the width and height
     /// guaranteed to be positive.
     pub fn sort(self) -> Self {
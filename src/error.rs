@@ -0,0 +1,110 @@
+//! Errors reported by the UI.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+
+use crate::layout::*;
+
+/// An error returned by the fallible `try_*` methods of [`Ui`][crate::Ui], such as [`Ui::try_push`][crate::Ui::try_push].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UiError {
+    /// There are no groups on the stack. This usually means that [`Ui::root`][crate::Ui::root] wasn't called.
+    EmptyStack,
+    /// The operation needs a parent group, but the current group is the root group.
+    MissingParent,
+    /// The operation can't be performed on the current group's layout.
+    InvalidLayout {
+        /// The name of the operation.
+        operation: &'static str,
+        /// The layout of the current group.
+        layout: Layout,
+    },
+    /// The current group uses the grid layout, but its tracks weren't declared using [`Ui::grid`][crate::Ui::grid].
+    MissingGrid,
+    /// All the items of the current group's flex plan were already used.
+    FlexPlanExhausted,
+    /// The current group isn't the content of a scroll area.
+    NotScrollArea,
+    /// The current group is the content of a scroll area, which must be popped using
+    /// [`Ui::pop_scroll`][crate::Ui::pop_scroll].
+    ScrollAreaNotPopped,
+}
+
+impl fmt::Display for UiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiError::EmptyStack => write!(
+                f,
+                "no groups on the stack. did you forget root(), or check your push() and pop()s"
+            ),
+            UiError::MissingParent => write!(
+                f,
+                "the current group is the root group, which has no parent. check your push() and pop()s"
+            ),
+            UiError::InvalidLayout { operation, layout } => {
+                write!(f, "using {}() on {:?} layout is forbidden", operation, layout)
+            }
+            UiError::MissingGrid => write!(
+                f,
+                "grid layout used without declaring tracks. check your grid()"
+            ),
+            UiError::FlexPlanExhausted => write!(
+                f,
+                "no items left in the flex plan. check your flex() and push_flex()es"
+            ),
+            UiError::NotScrollArea => write!(
+                f,
+                "the current group isn't a scroll area. check your push_scroll() and pop_scroll()s"
+            ),
+            UiError::ScrollAreaNotPopped => write!(
+                f,
+                "the current group is a scroll area, which must be popped with pop_scroll() rather than pop()"
+            ),
+        }
+    }
+}
+
+impl Error for UiError {}
+
+/// The error returned by [`Ui::finish`][crate::Ui::finish] when groups were left on the stack at the end of a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnbalancedGroups {
+    pub(crate) locations: Vec<Option<&'static Location<'static>>>,
+}
+
+impl UnbalancedGroups {
+    /// Returns the number of groups that weren't popped.
+    pub fn count(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns the source locations of the pushes that weren't popped, from the outermost to the innermost group.
+    /// Locations are only recorded in debug builds; in release builds, all of them are `None`.
+    pub fn locations(&self) -> &[Option<&'static Location<'static>>] {
+        &self.locations
+    }
+}
+
+impl fmt::Display for UnbalancedGroups {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} group(s) were left on the stack at the end of the frame. check your push() and pop()s",
+            self.count()
+        )?;
+        for location in &self.locations {
+            match location {
+                Some(location) => write!(f, "\n  unmatched push at {}", location)?,
+                None => write!(
+                    f,
+                    "\n  unmatched push (locations are only recorded in debug builds)"
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnbalancedGroups {}
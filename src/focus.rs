@@ -0,0 +1,59 @@
+//! Keyboard focus tracking.
+
+use crate::id::*;
+
+/// The focus state. Focusable widgets are registered in traversal order during every frame, and Tab navigation
+/// moves through the order registered during the previous frame.
+#[derive(Default)]
+pub(crate) struct Focus {
+    focused: Option<Id>,
+    /// Widgets registered during the current frame.
+    order: Vec<Id>,
+    /// Widgets registered during the previous frame.
+    previous_order: Vec<Id>,
+}
+
+impl Focus {
+    /// Starts a new frame. Focus is dropped if the focused widget wasn't registered during the previous frame.
+    pub(crate) fn next_frame(&mut self) {
+        std::mem::swap(&mut self.order, &mut self.previous_order);
+        self.order.clear();
+        if let Some(focused) = self.focused {
+            if !self.previous_order.contains(&focused) {
+                self.focused = None;
+            }
+        }
+    }
+
+    /// Registers a focusable widget.
+    pub(crate) fn register(&mut self, id: Id) {
+        self.order.push(id);
+    }
+
+    /// Moves focus to the next (or previous) widget registered during the previous frame, wrapping around at either
+    /// end. If no widget is focused, the first (or last) widget is focused.
+    pub(crate) fn navigate(&mut self, forward: bool) {
+        let order = &self.previous_order;
+        if order.is_empty() {
+            return;
+        }
+        let current = self
+            .focused
+            .and_then(|focused| order.iter().position(|&id| id == focused));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % order.len(),
+            (Some(index), false) => (index + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+        self.focused = Some(order[next]);
+    }
+
+    pub(crate) fn focused(&self) -> Option<Id> {
+        self.focused
+    }
+
+    pub(crate) fn set_focused(&mut self, id: Option<Id>) {
+        self.focused = id;
+    }
+}
@@ -0,0 +1,30 @@
+//! Stable widget identifiers.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// An identifier for a widget, stable across frames.
+///
+/// IDs are created by hashing a key together with the ID of the enclosing scope, using [`Ui::id`][crate::Ui::id].
+/// Scopes are pushed with [`Ui::push_id`][crate::Ui::push_id], so two widgets with the same key in different scopes
+/// (eg. different iterations of a loop) still get different IDs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Id(u64);
+
+impl Id {
+    /// The ID of the root scope.
+    pub const ROOT: Self = Self(0);
+
+    /// Derives a new ID from this ID and the given key.
+    pub fn with(self, key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        key.hash(&mut hasher);
+        Self(hasher.finish())
+    }
+
+    /// Returns the raw hash value of the ID.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,337 @@
+//! Fitting images into groups, and nine-slice scaling.
+
+#![warn(missing_copy_implementations)]
+
+use crate::common::*;
+use crate::layout::*;
+
+/// How an image is fit into a group by [`Ui::image`][crate::Ui::image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFit {
+    /// The image is stretched to fill the group, ignoring its aspect ratio.
+    Stretch,
+    /// The image is scaled to fit inside of the group while preserving its aspect ratio, and centered. This may leave
+    /// empty space on two of the group's sides.
+    Contain,
+    /// The image is scaled to cover the whole group while preserving its aspect ratio, and centered. The parts of the
+    /// image that don't fit are cropped off.
+    Cover,
+    /// The image is drawn at its natural size, centered in the group. The parts of the image that don't fit are
+    /// cropped off.
+    Center,
+}
+
+/// How the center of a nine-slice image fills the space between its edges.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NineSliceCenter {
+    /// The center is stretched along both axes.
+    Stretch,
+    /// The center is repeated at its natural size, starting from its top left corner. The tiles along the right and
+    /// bottom edges are cropped to fit.
+    Tile,
+}
+
+/// Computes the destination and source rectangles for drawing an image of the given size into the given rectangle,
+/// using the given fit mode. Returns `None` if there's nothing to draw.
+pub(crate) fn fit_image(rect: Rect, image_size: Vector, fit: ImageFit) -> Option<(Rect, Rect)> {
+    if image_size.x <= 0.0 || image_size.y <= 0.0 || rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return None;
+    }
+    let image = Rect::new(point(0.0, 0.0), image_size);
+    let centered = |size: Vector| Rect::new(rect.center() - size / 2.0, size);
+    Some(match fit {
+        ImageFit::Stretch => (rect, image),
+        ImageFit::Contain => {
+            let scale = f32::min(rect.width() / image_size.x, rect.height() / image_size.y);
+            (centered(image_size * scale), image)
+        }
+        ImageFit::Cover => {
+            let scale = f32::max(rect.width() / image_size.x, rect.height() / image_size.y);
+            let source_size = rect.size / scale;
+            let source = Rect::new(image.center() - source_size / 2.0, source_size);
+            (rect, source)
+        }
+        ImageFit::Center => {
+            let destination = centered(image_size).intersection(rect);
+            let offset = destination.position - centered(image_size).position;
+            (destination, Rect::new(offset, destination.size))
+        }
+    })
+}
+
+/// Splits a single axis of a nine-slice into its three parts. Returns the `(start, size)` pairs of the destination
+/// and source spans of each part.
+///
+/// If the destination is too small to fit both insets, they're scaled down proportionally so that the middle part
+/// is empty.
+fn slice_axis(
+    start: f32,
+    size: f32,
+    image_size: f32,
+    low: f32,
+    high: f32,
+) -> [((f32, f32), (f32, f32)); 3] {
+    let low = low.clamp(0.0, image_size);
+    let high = high.clamp(0.0, image_size - low);
+    let scale = if low + high > size {
+        size / (low + high)
+    } else {
+        1.0
+    };
+    let (destination_low, destination_high) = (low * scale, high * scale);
+    [
+        ((start, destination_low), (0.0, low)),
+        (
+            (
+                start + destination_low,
+                size - destination_low - destination_high,
+            ),
+            (low, image_size - low - high),
+        ),
+        (
+            (start + size - destination_high, destination_high),
+            (image_size - high, high),
+        ),
+    ]
+}
+
+/// Covers the destination rectangle with tiles of the given size, each of which shows the source rectangle. The tiles
+/// that don't fit into the destination are cropped, along with their sources.
+fn tile(destination: Rect, source: Rect, tile_size: Vector) -> impl Iterator<Item = (Rect, Rect)> {
+    let columns = (destination.width() / tile_size.x).ceil() as usize;
+    let rows = (destination.height() / tile_size.y).ceil() as usize;
+    (0..rows).flat_map(move |row| {
+        (0..columns).map(move |column| {
+            let position = destination.position + vector(column as f32, row as f32) * tile_size;
+            let size = vector(
+                f32::min(tile_size.x, destination.right() - position.x),
+                f32::min(tile_size.y, destination.bottom() - position.y),
+            );
+            let source_size = source.size * size / tile_size;
+            (
+                Rect::new(position, size),
+                Rect::new(source.position, source_size),
+            )
+        })
+    })
+}
+
+/// Computes the destination and source rectangles of the parts of a nine-slice image drawn into the given rectangle.
+/// The insets are expressed in the image's pixels, and specify how far the edges reach into the image. Parts that
+/// would be empty are skipped, and a tiled center is split into one part per tile.
+pub(crate) fn nine_slice(
+    rect: Rect,
+    image_size: Vector,
+    insets: Padding,
+    center: NineSliceCenter,
+) -> impl Iterator<Item = (Rect, Rect)> {
+    let rect = rect.sort();
+    let columns = slice_axis(
+        rect.x(),
+        rect.width(),
+        image_size.x,
+        insets.left,
+        insets.right,
+    );
+    let rows = slice_axis(
+        rect.y(),
+        rect.height(),
+        image_size.y,
+        insets.top,
+        insets.bottom,
+    );
+    (0..3)
+        .flat_map(move |row| (0..3).map(move |column| (row, column)))
+        .map(move |(row, column)| {
+            let ((x, source_x), (y, source_y)) = (columns[column], rows[row]);
+            (
+                (row, column),
+                Rect::new((x.0, y.0), (x.1, y.1)),
+                Rect::new((source_x.0, source_y.0), (source_x.1, source_y.1)),
+            )
+        })
+        .filter(|(_, destination, source)| {
+            destination.width() > 0.0
+                && destination.height() > 0.0
+                && source.width() > 0.0
+                && source.height() > 0.0
+        })
+        .flat_map(move |(part, destination, source)| {
+            let tile_size = match (part, center) {
+                ((1, 1), NineSliceCenter::Tile) => source.size,
+                _ => destination.size,
+            };
+            tile(destination, source, tile_size)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_modes() {
+        let rect = Rect::new((10.0, 10.0), (100.0, 50.0));
+        let size = vector(20.0, 20.0);
+        assert_eq!(
+            fit_image(rect, size, ImageFit::Stretch),
+            Some((rect, Rect::new((0.0, 0.0), (20.0, 20.0))))
+        );
+        assert_eq!(
+            fit_image(rect, size, ImageFit::Contain),
+            Some((
+                Rect::new((35.0, 10.0), (50.0, 50.0)),
+                Rect::new((0.0, 0.0), (20.0, 20.0))
+            ))
+        );
+        assert_eq!(
+            fit_image(rect, size, ImageFit::Cover),
+            Some((rect, Rect::new((0.0, 5.0), (20.0, 10.0))))
+        );
+        assert_eq!(
+            fit_image(rect, size, ImageFit::Center),
+            Some((
+                Rect::new((50.0, 25.0), (20.0, 20.0)),
+                Rect::new((0.0, 0.0), (20.0, 20.0))
+            ))
+        );
+        // images larger than the group are cropped
+        assert_eq!(
+            fit_image(rect, vector(200.0, 20.0), ImageFit::Center),
+            Some((
+                Rect::new((10.0, 25.0), (100.0, 20.0)),
+                Rect::new((50.0, 0.0), (100.0, 20.0))
+            ))
+        );
+        assert_eq!(fit_image(rect, vector(0.0, 0.0), ImageFit::Cover), None);
+    }
+
+    #[test]
+    fn nine_slice_parts() {
+        let insets = Padding {
+            left: 4.0,
+            right: 2.0,
+            top: 4.0,
+            bottom: 2.0,
+        };
+        let parts: Vec<_> = nine_slice(
+            Rect::new((10.0, 10.0), (100.0, 50.0)),
+            vector(16.0, 16.0),
+            insets,
+            NineSliceCenter::Stretch,
+        )
+        .collect();
+        assert_eq!(
+            parts,
+            [
+                // top row: corners are unscaled, the edge is stretched horizontally
+                (
+                    Rect::new((10.0, 10.0), (4.0, 4.0)),
+                    Rect::new((0.0, 0.0), (4.0, 4.0))
+                ),
+                (
+                    Rect::new((14.0, 10.0), (94.0, 4.0)),
+                    Rect::new((4.0, 0.0), (10.0, 4.0))
+                ),
+                (
+                    Rect::new((108.0, 10.0), (2.0, 4.0)),
+                    Rect::new((14.0, 0.0), (2.0, 4.0))
+                ),
+                // middle row: edges are stretched vertically, the center in both directions
+                (
+                    Rect::new((10.0, 14.0), (4.0, 44.0)),
+                    Rect::new((0.0, 4.0), (4.0, 10.0))
+                ),
+                (
+                    Rect::new((14.0, 14.0), (94.0, 44.0)),
+                    Rect::new((4.0, 4.0), (10.0, 10.0))
+                ),
+                (
+                    Rect::new((108.0, 14.0), (2.0, 44.0)),
+                    Rect::new((14.0, 4.0), (2.0, 10.0))
+                ),
+                // bottom row
+                (
+                    Rect::new((10.0, 58.0), (4.0, 2.0)),
+                    Rect::new((0.0, 14.0), (4.0, 2.0))
+                ),
+                (
+                    Rect::new((14.0, 58.0), (94.0, 2.0)),
+                    Rect::new((4.0, 14.0), (10.0, 2.0))
+                ),
+                (
+                    Rect::new((108.0, 58.0), (2.0, 2.0)),
+                    Rect::new((14.0, 14.0), (2.0, 2.0))
+                ),
+            ]
+        );
+
+        // rectangles smaller than the insets shrink the corners, and leave out the edges and center
+        let parts: Vec<_> = nine_slice(
+            Rect::new((0.0, 0.0), (3.0, 3.0)),
+            vector(16.0, 16.0),
+            insets,
+            NineSliceCenter::Stretch,
+        )
+        .map(|(destination, _)| destination)
+        .collect();
+        assert_eq!(
+            parts,
+            [
+                Rect::new((0.0, 0.0), (2.0, 2.0)),
+                Rect::new((2.0, 0.0), (1.0, 2.0)),
+                Rect::new((0.0, 2.0), (2.0, 1.0)),
+                Rect::new((2.0, 2.0), (1.0, 1.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn nine_slice_tiled_center() {
+        let insets = Padding {
+            left: 4.0,
+            right: 4.0,
+            top: 4.0,
+            bottom: 4.0,
+        };
+        // a 25×18 center is covered by 10×10 tiles, with the last column and row cropped
+        let parts: Vec<_> = nine_slice(
+            Rect::new((0.0, 0.0), (33.0, 26.0)),
+            vector(18.0, 18.0),
+            insets,
+            NineSliceCenter::Tile,
+        )
+        .skip(4)
+        .take(6)
+        .collect();
+        assert_eq!(
+            parts,
+            [
+                (
+                    Rect::new((4.0, 4.0), (10.0, 10.0)),
+                    Rect::new((4.0, 4.0), (10.0, 10.0))
+                ),
+                (
+                    Rect::new((14.0, 4.0), (10.0, 10.0)),
+                    Rect::new((4.0, 4.0), (10.0, 10.0))
+                ),
+                (
+                    Rect::new((24.0, 4.0), (5.0, 10.0)),
+                    Rect::new((4.0, 4.0), (5.0, 10.0))
+                ),
+                (
+                    Rect::new((4.0, 14.0), (10.0, 8.0)),
+                    Rect::new((4.0, 4.0), (10.0, 8.0))
+                ),
+                (
+                    Rect::new((14.0, 14.0), (10.0, 8.0)),
+                    Rect::new((4.0, 4.0), (10.0, 8.0))
+                ),
+                (
+                    Rect::new((24.0, 14.0), (5.0, 8.0)),
+                    Rect::new((4.0, 4.0), (5.0, 8.0))
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Input state snapshots, used for hit-testing groups.
+
+#![warn(missing_copy_implementations)]
+
+use crate::common::*;
+
+/// A pointer (mouse) button.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    fn index(self) -> usize {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+        }
+    }
+}
+
+/// A keyboard key used for navigating and operating widgets.
+///
+/// paws doesn't deal with text input, so only keys that are useful for interacting with widgets are included.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    Tab,
+    Enter,
+    Space,
+    Escape,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+impl Key {
+    /// The number of keys.
+    const COUNT: usize = Key::PageDown as usize + 1;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Keyboard modifier state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The "logo" key, also known as Super, Command, or the Windows key.
+    pub logo: bool,
+}
+
+/// The result of [`Ui::interact`][crate::Ui::interact]: how the pointer interacts with a widget during the current
+/// frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Interaction {
+    /// Whether the widget was the topmost widget under the pointer during the previous frame, is still hovered, and
+    /// no other widget is active. At most one widget is hot at a time.
+    pub hot: bool,
+    /// Whether the widget is active, ie. it was pressed and the button hasn't been released before this frame.
+    /// Active widgets capture the pointer, so they stay active even if the pointer leaves them.
+    pub active: bool,
+    /// Whether the widget was pressed during this frame, and became active.
+    pub pressed: bool,
+    /// Whether the button was released during this frame, deactivating the widget.
+    pub released: bool,
+    /// Whether the button was released during this frame while the pointer was still over the widget.
+    pub clicked: bool,
+}
+
+/// A snapshot of the input state for a single frame.
+///
+/// paws doesn't process input events by itself, so it's the user's job to feed events from their windowing library of
+/// choice into an `Input`, and pass it to the UI using `ui.set_input(input)` at the start of every frame. After the
+/// frame is done, [`Input::next_frame`] should be called to clear state that only lasts for a single frame, such as
+/// button presses and the scroll delta.
+///
+/// ```
+/// use paws::{Input, Layout, MouseButton, NoRenderer, Ui};
+///
+/// let mut ui = Ui::new(NoRenderer);
+/// let mut input = Input::new();
+///
+/// // ↓ these would usually come from your event loop
+/// input.set_pointer(Some(paws::point(32.0, 32.0)));
+/// input.press(MouseButton::Left);
+///
+/// ui.root((800.0, 600.0), Layout::Freeform);
+/// ui.set_input(input);
+/// ui.push((64.0, 64.0), Layout::Freeform);
+/// assert!(ui.hovered());
+/// assert!(ui.pressed(MouseButton::Left));
+/// ui.pop();
+///
+/// input.next_frame();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Input {
+    pointer: Option<Point>,
+    scroll: Vector,
+    modifiers: Modifiers,
+    down: [bool; 3],
+    pressed: [bool; 3],
+    released: [bool; 3],
+    /// How many times each key was pressed during this frame.
+    keys: [u8; Key::COUNT],
+}
+
+impl Input {
+    /// Creates a new input state, with the pointer outside of the window and all buttons released.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears all the state that only lasts for a single frame: button presses and releases, key presses, and the
+    /// scroll delta.
+    pub fn next_frame(&mut self) {
+        self.scroll = vector(0.0, 0.0);
+        self.pressed = [false; 3];
+        self.released = [false; 3];
+        self.keys = [0; Key::COUNT];
+    }
+
+    /// Returns the pointer position, or `None` if the pointer is outside of the window.
+    pub fn pointer(&self) -> Option<Point> {
+        self.pointer
+    }
+
+    /// Sets the pointer position. `None` should be used when the pointer leaves the window.
+    pub fn set_pointer(&mut self, position: Option<Point>) {
+        self.pointer = position;
+    }
+
+    /// Returns the scroll delta accumulated during this frame.
+    pub fn scroll(&self) -> Vector {
+        self.scroll
+    }
+
+    /// Accumulates the given scroll delta.
+    pub fn add_scroll(&mut self, delta: Vector) {
+        self.scroll += delta;
+    }
+
+    /// Returns the keyboard modifier state.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Sets the keyboard modifier state.
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    /// Marks the given button as pressed. The button stays down until it's released.
+    pub fn press(&mut self, button: MouseButton) {
+        self.down[button.index()] = true;
+        self.pressed[button.index()] = true;
+    }
+
+    /// Marks the given button as released.
+    pub fn release(&mut self, button: MouseButton) {
+        self.down[button.index()] = false;
+        self.released[button.index()] = true;
+    }
+
+    /// Returns whether the given button is currently held down.
+    pub fn is_down(&self, button: MouseButton) -> bool {
+        self.down[button.index()]
+    }
+
+    /// Returns whether the given button was pressed during this frame.
+    pub fn was_pressed(&self, button: MouseButton) -> bool {
+        self.pressed[button.index()]
+    }
+
+    /// Returns whether the given button was released during this frame.
+    pub fn was_released(&self, button: MouseButton) -> bool {
+        self.released[button.index()]
+    }
+
+    /// Marks the given key as pressed during this frame. Key repeats should be fed in as additional presses.
+    pub fn press_key(&mut self, key: Key) {
+        let presses = &mut self.keys[key.index()];
+        *presses = presses.saturating_add(1);
+    }
+
+    /// Returns whether the given key was pressed during this frame.
+    pub fn was_key_pressed(&self, key: Key) -> bool {
+        self.keys[key.index()] > 0
+    }
+
+    /// Returns how many times the given key was pressed during this frame, including repeats.
+    pub fn key_presses(&self, key: Key) -> usize {
+        self.keys[key.index()].into()
+    }
+}
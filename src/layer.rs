@@ -0,0 +1,212 @@
+//! Z-ordered layers for drawing overlays on top of the rest of the UI.
+
+use crate::common::*;
+use crate::image::*;
+use crate::layout::*;
+use crate::recording::*;
+use crate::renderer::*;
+
+/// A layer that groups are drawn onto. Layers are drawn in order, so [`Layer::Tooltip`] ends up above
+/// [`Layer::Overlay`], which ends up above [`Layer::Normal`].
+///
+/// Drawing onto the normal layer happens immediately. Draw calls issued onto other layers are buffered, and flushed
+/// into the renderer upon calling [`Ui::flush_layers`][crate::Ui::flush_layers].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// The rest of the UI, which is drawn immediately.
+    Normal,
+    /// Dropdowns, context menus, popups.
+    Overlay,
+    /// Tooltips, which are drawn above everything else.
+    Tooltip,
+}
+
+impl Layer {
+    /// Returns the index of the layer's buffer, or `None` if the layer isn't deferred.
+    pub(crate) fn buffer_index(self) -> Option<usize> {
+        match self {
+            Layer::Normal => None,
+            Layer::Overlay => Some(0),
+            Layer::Tooltip => Some(1),
+        }
+    }
+}
+
+/// The buffered draw commands of a deferred layer.
+pub(crate) type LayerBuffer<T> = Vec<DrawCommand<<T as Renderer>::Font, <T as Renderer>::Image>>;
+
+/// Functions for cloning fonts and images, which are needed for buffering draw calls on deferred layers. These are
+/// captured by [`Ui::set_layer`][crate::Ui::set_layer], which is the only way of moving groups onto deferred layers,
+/// so that drawing onto the normal layer doesn't require fonts and images to implement `Clone`.
+pub(crate) struct Cloner<T: Renderer> {
+    pub(crate) font: fn(&T::Font) -> T::Font,
+    pub(crate) image: fn(&T::Image) -> T::Image,
+}
+
+impl<T> Cloner<T>
+where
+    T: Renderer,
+    T::Font: Clone,
+    T::Image: Clone,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            font: T::Font::clone,
+            image: T::Image::clone,
+        }
+    }
+}
+
+impl<T: Renderer> Clone for Cloner<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Renderer> Copy for Cloner<T> {}
+
+/// A renderer that draws onto the current group's layer. This is obtained using [`Ui::painter`][crate::Ui::painter].
+///
+/// On the normal layer, calls are forwarded to the underlying renderer directly. On other layers, they're buffered
+/// until the layers are flushed. Because buffered text isn't drawn immediately, its advance can't be known, so
+/// [`Renderer::text`] returns 0 on buffered layers.
+pub struct Painter<'a, T: Renderer> {
+    pub(crate) renderer: &'a mut T,
+    pub(crate) buffer: Option<(&'a mut LayerBuffer<T>, Cloner<T>)>,
+}
+
+impl<'a, T: Renderer> Renderer for Painter<'a, T> {
+    type Font = T::Font;
+    type Image = T::Image;
+
+    fn push(&mut self) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Push),
+            None => self.renderer.push(),
+        }
+    }
+
+    fn pop(&mut self) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Pop),
+            None => self.renderer.pop(),
+        }
+    }
+
+    fn translate(&mut self, vec: Vector) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Translate(vec)),
+            None => self.renderer.translate(vec),
+        }
+    }
+
+    fn clip(&mut self, rect: Rect) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Clip(rect)),
+            None => self.renderer.clip(rect),
+        }
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color, radius: f32) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Fill {
+                rect,
+                color,
+                radius,
+            }),
+            None => self.renderer.fill(rect, color, radius),
+        }
+    }
+
+    fn outline(&mut self, rect: Rect, color: Color, radius: f32, thickness: f32) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Outline {
+                rect,
+                color,
+                radius,
+                thickness,
+            }),
+            None => self.renderer.outline(rect, color, radius, thickness),
+        }
+    }
+
+    fn line(&mut self, a: Point, b: Point, color: Color, cap: LineCap, thickness: f32) {
+        match &mut self.buffer {
+            Some((buffer, _)) => buffer.push(DrawCommand::Line {
+                a,
+                b,
+                color,
+                cap,
+                thickness,
+            }),
+            None => self.renderer.line(a, b, color, cap, thickness),
+        }
+    }
+
+    fn text(
+        &mut self,
+        rect: Rect,
+        font: &Self::Font,
+        text: &str,
+        color: Color,
+        alignment: Alignment,
+    ) -> f32 {
+        match &mut self.buffer {
+            Some((buffer, cloner)) => {
+                buffer.push(DrawCommand::Text {
+                    rect,
+                    font: (cloner.font)(font),
+                    text: text.to_owned(),
+                    color,
+                    alignment,
+                });
+                0.0
+            }
+            None => self.renderer.text(rect, font, text, color, alignment),
+        }
+    }
+
+    fn measure_text(&self, font: &Self::Font, text: &str) -> TextMetrics {
+        self.renderer.measure_text(font, text)
+    }
+
+    fn image(&mut self, rect: Rect, image: &Self::Image, source: Rect, tint: Color) {
+        match &mut self.buffer {
+            Some((buffer, cloner)) => buffer.push(DrawCommand::Image {
+                rect,
+                image: (cloner.image)(image),
+                source,
+                tint,
+            }),
+            None => self.renderer.image(rect, image, source, tint),
+        }
+    }
+
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        self.renderer.image_size(image)
+    }
+
+    fn nine_slice(
+        &mut self,
+        rect: Rect,
+        image: &Self::Image,
+        insets: Padding,
+        center: NineSliceCenter,
+        tint: Color,
+    ) {
+        match &mut self.buffer {
+            Some((buffer, cloner)) => {
+                let image_size = self.renderer.image_size(image);
+                for (rect, source) in nine_slice(rect, image_size, insets, center) {
+                    buffer.push(DrawCommand::Image {
+                        rect,
+                        image: (cloner.image)(image),
+                        source,
+                        tint,
+                    });
+                }
+            }
+            None => self.renderer.nine_slice(rect, image, insets, center, tint),
+        }
+    }
+}
@@ -2,8 +2,10 @@
 
 #![warn(missing_copy_implementations)]
 
+use crate::common::*;
+
 /// Group layout type. This defines how subgroups are arranged inside of a group.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Layout {
     /// The layout for individual subgroups is defined by the user via `ui.set_cursor(x, y)`.
     Freeform,
@@ -19,10 +21,395 @@ pub enum Layout {
     /// Subgroups are laid out vertically, from bottom to top. The default starting point for layout is the
     /// lower-left corner of the group.
     VerticalRev,
+    /// Subgroups are laid out horizontally, from left to right, in lines going from top to bottom. Whenever a
+    /// subgroup would overflow the right edge of the group, it's moved to a new line below the tallest subgroup of the
+    /// current line. The gaps between subgroups and lines can be set using `ui.set_gap(main, cross)`.
+    HorizontalWrap,
+    /// Subgroups are laid out vertically, from top to bottom, in columns going from left to right. Whenever a
+    /// subgroup would overflow the bottom edge of the group, it's moved to a new column to the right of the widest
+    /// subgroup of the current column. The gaps between subgroups and columns can be set using
+    /// `ui.set_gap(main, cross)`.
+    VerticalWrap,
+    /// Subgroups are laid out in a grid of cells, whose column and row tracks are declared using
+    /// `ui.grid(columns, rows)`. Each pushed subgroup lands in the next free cell, going from left to right, top to
+    /// bottom. The gaps between columns (main) and rows (cross) can be set using `ui.set_gap(main, cross)`.
+    Grid,
+}
+
+/// Sizing rule for a single child in a flex plan, as declared using `ui.flex(items)`.
+///
+/// Each child starts out with its `basis` size. Then, any leftover space along the group's main axis is distributed
+/// among children with a non-zero `weight`, proportionally to their weights. The resulting sizes are clamped to the
+/// range `min..=max`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlexItem {
+    pub basis: f32,
+    pub weight: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl FlexItem {
+    /// Creates an item with a fixed size, which doesn't take part in distributing the leftover space.
+    pub fn fixed(size: f32) -> Self {
+        Self {
+            basis: size,
+            weight: 0.0,
+            min: size,
+            max: size,
+        }
+    }
+
+    /// Creates an item that takes up a share of the leftover space proportional to the given weight.
+    pub fn grow(weight: f32) -> Self {
+        Self {
+            basis: 0.0,
+            weight,
+            min: 0.0,
+            max: f32::INFINITY,
+        }
+    }
+
+    /// Sets the item's basis size, which is the size the item has before the leftover space is distributed.
+    pub fn basis(self, basis: f32) -> Self {
+        Self { basis, ..self }
+    }
+
+    /// Sets the item's minimum size.
+    pub fn min(self, min: f32) -> Self {
+        Self { min, ..self }
+    }
+
+    /// Sets the item's maximum size.
+    pub fn max(self, max: f32) -> Self {
+        Self { max, ..self }
+    }
+}
+
+/// Resolves the sizes of flex items, such that they fill the available space.
+///
+/// Sizes are distributed in a similar manner to CSS flexbox: whenever an item's size gets clamped to its minimum or
+/// maximum size, it's frozen at that size and the remaining space is distributed again among the other items.
+///
+/// If the available space isn't finite, there's no free space to distribute, and every item keeps its basis size.
+pub(crate) fn resolve_flex(items: &[FlexItem], available: f32) -> Vec<f32> {
+    let clamp = |item: &FlexItem, size: f32| size.min(item.max).max(item.min);
+    let mut sizes: Vec<f32> = items.iter().map(|item| clamp(item, item.basis)).collect();
+    let mut frozen: Vec<bool> = items.iter().map(|item| item.weight <= 0.0).collect();
+    // every pass either finishes or freezes at least one item, so this many passes are always enough
+    for _ in 0..items.len() {
+        let mut free = available;
+        let mut total_weight = 0.0;
+        for (i, item) in items.iter().enumerate() {
+            if frozen[i] {
+                free -= sizes[i];
+            } else {
+                free -= item.basis;
+                total_weight += item.weight;
+            }
+        }
+        if total_weight <= 0.0 || !free.is_finite() {
+            break;
+        }
+
+        let mut total_violation = 0.0;
+        for (i, item) in items.iter().enumerate() {
+            if !frozen[i] {
+                let size = item.basis + free * item.weight / total_weight;
+                sizes[i] = clamp(item, size);
+                total_violation += sizes[i] - size;
+            }
+        }
+        if total_violation == 0.0 {
+            break;
+        }
+        // freeze the items that got clamped in the direction of the total violation
+        for (i, item) in items.iter().enumerate() {
+            if !frozen[i] {
+                let size = item.basis + free * item.weight / total_weight;
+                if (total_violation > 0.0 && sizes[i] > size)
+                    || (total_violation < 0.0 && sizes[i] < size)
+                {
+                    frozen[i] = true;
+                }
+            }
+        }
+    }
+    sizes
+}
+
+/// Sizing rule for a single column or row in a grid, as declared using `ui.grid(columns, rows)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Track {
+    /// The track has a fixed size.
+    Fixed(f32),
+    /// The track takes up a share of the space left over by fixed and auto tracks, proportional to the given
+    /// fraction. As auto tracks grow, fractional tracks shrink accordingly.
+    Fraction(f32),
+    /// The track grows to fit the largest subgroup placed in it. Subgroups spanning multiple tracks don't affect the
+    /// size of auto tracks.
+    ///
+    /// Because groups are laid out as they're pushed, an auto track only grows when a subgroup is popped, and tracks
+    /// after it are moved only for subgroups pushed after that. Cells are filled row by row, so auto rows always
+    /// enclose all their subgroups, but auto columns only enclose the subgroups in rows that come after the widest
+    /// one. Thus, auto columns are best used when the first row holds the widest subgroups, such as table headers.
+    Auto,
+}
+
+/// The state of a grid layout.
+#[derive(Clone)]
+pub(crate) struct Grid {
+    column_tracks: Vec<Track>,
+    row_tracks: Vec<Track>,
+    /// The resolved sizes of the tracks.
+    columns: Vec<f32>,
+    rows: Vec<f32>,
+    size: Vector,
+    gap: Vector,
+    /// For each column, the first row that isn't occupied by a subgroup.
+    occupied: Vec<usize>,
+    /// The cell where auto-placement continues.
+    cursor: (usize, usize),
+    /// Explicit placement and span for the next subgroup.
+    next_cell: Option<(usize, usize)>,
+    next_span: (usize, usize),
+    /// The cell and span of the last placed subgroup.
+    current: ((usize, usize), (usize, usize)),
+}
+
+impl Grid {
+    /// Creates a new grid from the given tracks, resolving fixed and fractional tracks to fill the given size.
+    pub(crate) fn new(columns: &[Track], rows: &[Track], size: Vector, gap: Vector) -> Self {
+        assert!(!columns.is_empty(), "grids must have at least one column");
+        Self {
+            columns: resolve_tracks(columns, &[], size.x, gap.x),
+            rows: resolve_tracks(rows, &[], size.y, gap.y),
+            column_tracks: columns.to_vec(),
+            row_tracks: rows.to_vec(),
+            size,
+            gap,
+            occupied: vec![0; columns.len()],
+            cursor: (0, 0),
+            next_cell: None,
+            next_span: (1, 1),
+            current: ((0, 0), (1, 1)),
+        }
+    }
+
+    /// Resolves the tracks again to fill the given size, with the given gaps. This is used when the grid's group is
+    /// padded or its gaps change after the tracks were declared.
+    pub(crate) fn resize(&mut self, size: Vector, gap: Vector) {
+        self.size = size;
+        self.gap = gap;
+        self.columns = resolve_tracks(&self.column_tracks, &self.columns, size.x, gap.x);
+        self.rows = resolve_tracks(&self.row_tracks, &self.rows, size.y, gap.y);
+    }
+
+    /// Sets the cell at which the next subgroup is placed.
+    pub(crate) fn set_cell(&mut self, column: usize, row: usize) {
+        assert!(
+            column < self.columns.len(),
+            "grid column {} out of bounds",
+            column
+        );
+        self.next_cell = Some((column, row));
+    }
+
+    /// Sets the span of the next subgroup.
+    pub(crate) fn set_span(&mut self, columns: usize, rows: usize) {
+        assert!(columns > 0 && rows > 0, "grid spans must not be empty");
+        self.next_span = (columns.min(self.columns.len()), rows);
+    }
+
+    /// Returns the cell and span at which the next subgroup will be placed.
+    fn next(&self) -> ((usize, usize), (usize, usize)) {
+        let span = self.next_span;
+        if let Some(cell) = self.next_cell {
+            return (cell, (span.0.min(self.columns.len() - cell.0), span.1));
+        }
+        let (mut column, mut row) = self.cursor;
+        loop {
+            if column + span.0 > self.columns.len() {
+                column = 0;
+                row += 1;
+            }
+            if self.occupied[column..column + span.0]
+                .iter()
+                .all(|&occupied| occupied <= row)
+            {
+                return ((column, row), span);
+            }
+            column += 1;
+        }
+    }
+
+    /// Returns the rectangle of the next subgroup's cell, relative to the grid's position.
+    pub(crate) fn next_rect(&self) -> Rect {
+        let (cell, span) = self.next();
+        self.rect(cell, span)
+    }
+
+    /// Places the next subgroup, returning its cell's rectangle relative to the grid's position.
+    pub(crate) fn place(&mut self) -> Rect {
+        let ((column, row), span) = self.next();
+        self.ensure_rows(row + span.1);
+        for occupied in &mut self.occupied[column..column + span.0] {
+            *occupied = (*occupied).max(row + span.1);
+        }
+        self.cursor = (column + span.0, row);
+        self.next_cell = None;
+        self.next_span = (1, 1);
+        self.current = ((column, row), span);
+        self.rect((column, row), span)
+    }
+
+    /// Returns the rectangle of the last placed subgroup's cell, relative to the grid's position.
+    pub(crate) fn current_rect(&self) -> Rect {
+        self.rect(self.current.0, self.current.1)
+    }
+
+    /// Grows the auto tracks of the last placed subgroup to fit the given size.
+    pub(crate) fn fit_current(&mut self, size: Vector) {
+        let ((column, row), span) = self.current;
+        self.ensure_rows(row + 1);
+        if span.0 == 1 && self.column_tracks[column] == Track::Auto && size.x > self.columns[column]
+        {
+            self.columns[column] = size.x;
+            self.columns =
+                resolve_tracks(&self.column_tracks, &self.columns, self.size.x, self.gap.x);
+        }
+        if span.1 == 1 && self.row_tracks[row] == Track::Auto && size.y > self.rows[row] {
+            self.rows[row] = size.y;
+            self.rows = resolve_tracks(&self.row_tracks, &self.rows, self.size.y, self.gap.y);
+        }
+    }
+
+    /// Returns the total size of all tracks, including gaps.
+    pub(crate) fn size(&self) -> Vector {
+        let rows = self
+            .occupied
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(self.rows.len());
+        vector(
+            track_offset(&self.columns, self.gap.x, self.columns.len()) - self.gap.x,
+            (track_offset(&self.rows, self.gap.y, rows) - self.gap.y).max(0.0),
+        )
+    }
+
+    /// Adds implicit auto rows, such that the grid has at least the given amount of rows.
+    fn ensure_rows(&mut self, count: usize) {
+        while self.rows.len() < count {
+            self.rows.push(0.0);
+            self.row_tracks.push(Track::Auto);
+        }
+    }
+
+    /// Returns the rectangle spanning the given cells.
+    fn rect(&self, (column, row): (usize, usize), (columns, rows): (usize, usize)) -> Rect {
+        let left = track_offset(&self.columns, self.gap.x, column);
+        let top = track_offset(&self.rows, self.gap.y, row);
+        let right = track_offset(&self.columns, self.gap.x, column + columns) - self.gap.x;
+        let bottom = track_offset(&self.rows, self.gap.y, row + rows) - self.gap.y;
+        Rect::new(
+            point(left, top),
+            vector(right - left, (bottom - top).max(0.0)),
+        )
+    }
+}
+
+/// Resolves the sizes of grid tracks, such that they fill the available space. The current sizes of auto tracks are
+/// taken from `sizes`; auto tracks past its end are empty.
+fn resolve_tracks(tracks: &[Track], sizes: &[f32], available: f32, gap: f32) -> Vec<f32> {
+    let items: Vec<FlexItem> = tracks
+        .iter()
+        .enumerate()
+        .map(|(i, track)| match *track {
+            Track::Fixed(size) => FlexItem::fixed(size),
+            Track::Fraction(fraction) => FlexItem::grow(fraction),
+            Track::Auto => FlexItem::fixed(sizes.get(i).copied().unwrap_or(0.0)),
+        })
+        .collect();
+    let gaps = gap * tracks.len().saturating_sub(1) as f32;
+    resolve_flex(&items, available - gaps)
+}
+
+/// Scroll state of a scroll area, as used by `ui.push_scroll(size, layout, &state)` and `ui.pop_scroll(&mut state)`.
+///
+/// Since paws doesn't preserve any state across frames, this should be kept alongside the rest of your element's
+/// state. The content and viewport sizes are updated every time the scroll area is popped, so they always describe
+/// the last frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScrollState {
+    /// The scroll offset. Positive values move the content up and to the left.
+    pub offset: Vector,
+    /// The size of the content, as measured when the scroll area was last popped.
+    pub content_size: Vector,
+    /// The size of the viewport, as measured when the scroll area was last popped.
+    pub viewport_size: Vector,
+}
+
+impl ScrollState {
+    /// Creates a new scroll state, scrolled to the upper-left corner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the maximum scroll offset, at which the content's lower-right corner is aligned with the viewport's
+    /// lower-right corner. If the content is smaller than the viewport along an axis, the maximum offset along that
+    /// axis is 0.
+    pub fn max_offset(&self) -> Vector {
+        let max = self.content_size - self.viewport_size;
+        vector(max.x.max(0.0), max.y.max(0.0))
+    }
+
+    /// Clamps the scroll offset to the range `0..=max_offset()`.
+    pub fn clamp(&mut self) {
+        let max = self.max_offset();
+        self.offset = vector(
+            self.offset.x.min(max.x).max(0.0),
+            self.offset.y.min(max.y).max(0.0),
+        );
+    }
+
+    /// Scrolls by the given amount, clamping the offset afterwards.
+    pub fn scroll_by(&mut self, delta: Vector) {
+        self.offset += delta;
+        self.clamp();
+    }
+
+    /// Returns whether the content overflows the viewport horizontally, such that a horizontal scrollbar is needed.
+    pub fn overflows_x(&self) -> bool {
+        self.content_size.x > self.viewport_size.x
+    }
+
+    /// Returns whether the content overflows the viewport vertically, such that a vertical scrollbar is needed.
+    pub fn overflows_y(&self) -> bool {
+        self.content_size.y > self.viewport_size.y
+    }
+}
+
+impl Default for ScrollState {
+    /// The default scroll state has all of its offset and sizes set to `0.0`.
+    fn default() -> Self {
+        Self {
+            offset: vector(0.0, 0.0),
+            content_size: vector(0.0, 0.0),
+            viewport_size: vector(0.0, 0.0),
+        }
+    }
+}
+
+/// Returns the offset of the track at the given index, including gaps. Tracks past the end are treated as empty.
+fn track_offset(tracks: &[f32], gap: f32, index: usize) -> f32 {
+    let sizes: f32 = tracks.iter().take(index).sum();
+    sizes + gap * index as f32
 }
 
 /// Horizontal alignment position.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum AlignH {
     Left,
     Center,
@@ -30,7 +417,7 @@ pub enum AlignH {
 }
 
 /// Vertical alignment position.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum AlignV {
     Top,
     Middle,
@@ -50,7 +437,7 @@ pub const CENTER: Alignment = (Center, Middle);
 ///
 /// Usually you don't need to construct this directly, as this implements From for several types, and paws
 /// accepts `impl Into<Padding>` instead of just `Padding` in all functions.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Padding {
     pub right: f32,
     pub bottom: f32,
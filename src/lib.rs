@@ -3,12 +3,41 @@
 
 mod build;
 mod common;
+mod error;
+mod focus;
+mod id;
+mod image;
+mod input;
+mod layer;
 mod layout;
+mod recording;
 mod renderer;
+#[cfg(feature = "software")]
+mod software;
+mod state;
+#[cfg(feature = "svg")]
+mod svg;
+#[cfg(feature = "terminal")]
+mod terminal;
+mod text;
 mod ui;
 
+#[allow(unused_imports)]
 pub use build::*;
 pub use common::*;
+pub use error::*;
+pub use id::*;
+pub use image::*;
+pub use input::*;
+pub use layer::*;
 pub use layout::*;
+pub use recording::*;
 pub use renderer::*;
+#[cfg(feature = "software")]
+pub use software::*;
+#[cfg(feature = "svg")]
+pub use svg::*;
+#[cfg(feature = "terminal")]
+pub use terminal::*;
+pub use text::*;
 pub use ui::*;
@@ -0,0 +1,355 @@
+//! A renderer that records draw commands for later replay and inspection.
+
+use crate::common::*;
+use crate::layout::*;
+use crate::renderer::*;
+
+/// A single recorded call to one of [`Renderer`]'s methods. `F` and `I` are the font and image types used by the
+/// renderer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand<F, I> {
+    /// A call to [`Renderer::push`].
+    Push,
+    /// A call to [`Renderer::pop`].
+    Pop,
+    /// A call to [`Renderer::translate`].
+    Translate(Vector),
+    /// A call to [`Renderer::clip`].
+    Clip(Rect),
+    /// A call to [`Renderer::fill`].
+    Fill {
+        rect: Rect,
+        color: Color,
+        radius: f32,
+    },
+    /// A call to [`Renderer::outline`].
+    Outline {
+        rect: Rect,
+        color: Color,
+        radius: f32,
+        thickness: f32,
+    },
+    /// A call to [`Renderer::line`].
+    Line {
+        a: Point,
+        b: Point,
+        color: Color,
+        cap: LineCap,
+        thickness: f32,
+    },
+    /// A call to [`Renderer::text`].
+    Text {
+        rect: Rect,
+        font: F,
+        text: String,
+        color: Color,
+        alignment: Alignment,
+    },
+    /// A call to [`Renderer::image`].
+    Image {
+        rect: Rect,
+        image: I,
+        source: Rect,
+        tint: Color,
+    },
+}
+
+impl<F, I> DrawCommand<F, I> {
+    /// Executes the command on the given renderer.
+    pub fn execute<R>(&self, renderer: &mut R)
+    where
+        R: Renderer<Font = F, Image = I>,
+    {
+        match self {
+            DrawCommand::Push => renderer.push(),
+            DrawCommand::Pop => renderer.pop(),
+            DrawCommand::Translate(vec) => renderer.translate(*vec),
+            DrawCommand::Clip(rect) => renderer.clip(*rect),
+            DrawCommand::Fill {
+                rect,
+                color,
+                radius,
+            } => renderer.fill(*rect, *color, *radius),
+            DrawCommand::Outline {
+                rect,
+                color,
+                radius,
+                thickness,
+            } => renderer.outline(*rect, *color, *radius, *thickness),
+            DrawCommand::Line {
+                a,
+                b,
+                color,
+                cap,
+                thickness,
+            } => renderer.line(*a, *b, *color, *cap, *thickness),
+            DrawCommand::Text {
+                rect,
+                font,
+                text,
+                color,
+                alignment,
+            } => {
+                renderer.text(*rect, font, text, *color, *alignment);
+            }
+            DrawCommand::Image {
+                rect,
+                image,
+                source,
+                tint,
+            } => renderer.image(*rect, image, *source, *tint),
+        }
+    }
+}
+
+/// Executes all the given commands on the given renderer, in order.
+pub fn replay<R: Renderer>(commands: &[DrawCommand<R::Font, R::Image>], renderer: &mut R) {
+    for command in commands {
+        command.execute(renderer);
+    }
+}
+
+/// A renderer that records all the calls made to it into a list of [`DrawCommand`]s, which can then be replayed
+/// using [`replay`] into any other renderer that uses the same font and image types.
+///
+/// The recording renderer wraps another renderer, to which all calls are forwarded after they're recorded. This inner
+/// renderer is what provides the return values of methods like [`Renderer::text`]. If you only want to record
+/// commands, use [`NoRenderer`] as the inner renderer:
+///
+/// ```
+/// use paws::{DrawCommand, Layout, NoRenderer, RecordingRenderer, Ui};
+///
+/// let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+/// ui.root((800.0, 600.0), Layout::Freeform);
+/// ui.fill_rounded(paws::rgb(255, 255, 255), 4.0);
+/// assert!(matches!(ui.renderer().commands(), [DrawCommand::Fill { radius, .. }] if *radius == 4.0));
+/// ```
+///
+/// Because the commands are owned, recording requires the font and image types to implement `Clone`. For fonts and
+/// images that are expensive to clone, consider using reference-counted pointers as the renderer's font and image
+/// types.
+pub struct RecordingRenderer<T: Renderer> {
+    inner: T,
+    commands: Vec<DrawCommand<T::Font, T::Image>>,
+}
+
+impl<T: Renderer> RecordingRenderer<T> {
+    /// Creates a new recording renderer that forwards all calls to the given renderer.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Returns the commands recorded so far.
+    pub fn commands(&self) -> &[DrawCommand<T::Font, T::Image>] {
+        &self.commands
+    }
+
+    /// Takes the commands recorded so far out of the renderer, leaving its list of commands empty.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand<T::Font, T::Image>> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Clears the list of recorded commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Executes the commands recorded so far on the given renderer.
+    pub fn replay<R>(&self, renderer: &mut R)
+    where
+        R: Renderer<Font = T::Font, Image = T::Image>,
+    {
+        replay(&self.commands, renderer);
+    }
+
+    /// Returns an immutable reference to the inner renderer.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner renderer. Calls made to the inner renderer directly aren't recorded.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the recording renderer, returning the inner renderer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Renderer for RecordingRenderer<T>
+where
+    T: Renderer,
+    T::Font: Clone,
+    T::Image: Clone,
+{
+    type Font = T::Font;
+    type Image = T::Image;
+
+    fn push(&mut self) {
+        self.commands.push(DrawCommand::Push);
+        self.inner.push();
+    }
+
+    fn pop(&mut self) {
+        self.commands.push(DrawCommand::Pop);
+        self.inner.pop();
+    }
+
+    fn translate(&mut self, vec: Vector) {
+        self.commands.push(DrawCommand::Translate(vec));
+        self.inner.translate(vec);
+    }
+
+    fn clip(&mut self, rect: Rect) {
+        self.commands.push(DrawCommand::Clip(rect));
+        self.inner.clip(rect);
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color, radius: f32) {
+        self.commands.push(DrawCommand::Fill {
+            rect,
+            color,
+            radius,
+        });
+        self.inner.fill(rect, color, radius);
+    }
+
+    fn outline(&mut self, rect: Rect, color: Color, radius: f32, thickness: f32) {
+        self.commands.push(DrawCommand::Outline {
+            rect,
+            color,
+            radius,
+            thickness,
+        });
+        self.inner.outline(rect, color, radius, thickness);
+    }
+
+    fn line(&mut self, a: Point, b: Point, color: Color, cap: LineCap, thickness: f32) {
+        self.commands.push(DrawCommand::Line {
+            a,
+            b,
+            color,
+            cap,
+            thickness,
+        });
+        self.inner.line(a, b, color, cap, thickness);
+    }
+
+    fn text(
+        &mut self,
+        rect: Rect,
+        font: &Self::Font,
+        text: &str,
+        color: Color,
+        alignment: Alignment,
+    ) -> f32 {
+        self.commands.push(DrawCommand::Text {
+            rect,
+            font: font.clone(),
+            text: text.to_owned(),
+            color,
+            alignment,
+        });
+        self.inner.text(rect, font, text, color, alignment)
+    }
+
+    fn measure_text(&self, font: &Self::Font, text: &str) -> TextMetrics {
+        self.inner.measure_text(font, text)
+    }
+
+    fn image(&mut self, rect: Rect, image: &Self::Image, source: Rect, tint: Color) {
+        self.commands.push(DrawCommand::Image {
+            rect,
+            image: image.clone(),
+            source,
+            tint,
+        });
+        self.inner.image(rect, image, source, tint);
+    }
+
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        self.inner.image_size(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ui;
+
+    const WHITE: Color = Color::WHITE;
+
+    fn ui() -> Ui<RecordingRenderer<NoRenderer>> {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui
+    }
+
+    #[test]
+    fn records_fills_and_borders() {
+        let mut ui = ui();
+        ui.push((100.0, 50.0), Layout::Freeform);
+        ui.fill_rounded(WHITE, 4.0);
+        ui.set_line_cap(LineCap::Square);
+        ui.border_bottom(WHITE, 2.0);
+        ui.pop();
+
+        let rect = Rect::new((0.0, 0.0), (100.0, 50.0));
+        assert_eq!(
+            ui.renderer().commands(),
+            &[
+                DrawCommand::Fill {
+                    rect,
+                    color: WHITE,
+                    radius: 4.0
+                },
+                DrawCommand::Line {
+                    a: point(0.0, 50.0),
+                    b: point(100.0, 50.0),
+                    color: WHITE,
+                    cap: LineCap::Square,
+                    thickness: 2.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_draw_stack() {
+        let mut ui = ui();
+        ui.push((100.0, 50.0), Layout::Freeform);
+        ui.pop();
+        ui.push((100.0, 50.0), Layout::Freeform);
+        ui.clip();
+        ui.draw(|_| ());
+        ui.pop();
+
+        assert_eq!(
+            ui.renderer().commands(),
+            &[
+                DrawCommand::Clip(Rect::new((0.0, 50.0), (100.0, 50.0))),
+                DrawCommand::Push,
+                DrawCommand::Translate(vector(0.0, 50.0)),
+                DrawCommand::Pop,
+            ]
+        );
+    }
+
+    #[test]
+    fn replays_into_another_renderer() {
+        let mut ui = ui();
+        ui.fill(WHITE);
+        ui.text(&NoRendererFont, "hello", WHITE, CENTER);
+        let commands = ui.render().take_commands();
+        assert!(ui.renderer().commands().is_empty());
+
+        let mut target = RecordingRenderer::new(NoRenderer);
+        replay(&commands, &mut target);
+        assert_eq!(target.commands(), &commands[..]);
+    }
+}
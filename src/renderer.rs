@@ -1,10 +1,11 @@
 //! Abstract renderer trait. Required if you want any of the extra rendering functions to work.
 
 use crate::common::*;
+use crate::image::*;
 use crate::layout::*;
 
 /// The type of line cap to use when rendering.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum LineCap {
     /// The ends are not extended.
     Butt,
@@ -14,6 +15,27 @@ pub enum LineCap {
     Round,
 }
 
+/// The metrics of a single line of text, as measured by [`Renderer::measure_text`].
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct TextMetrics {
+    /// The horizontal advance of the text.
+    pub width: f32,
+    /// The distance from the baseline to the top of the tallest glyphs of the font.
+    pub ascent: f32,
+    /// The distance from the baseline to the bottom of the lowest glyphs of the font. This is positive for glyphs
+    /// that extend below the baseline.
+    pub descent: f32,
+    /// The distance between the baselines of two consecutive lines of text.
+    pub line_height: f32,
+}
+
+impl TextMetrics {
+    /// Returns the size of a box that fits a single line of the text.
+    pub fn size(&self) -> Vector {
+        vector(self.width, self.line_height)
+    }
+}
+
 /// The renderer trait, used for all things drawing-related.
 ///
 /// ## A note on rendering lines
@@ -26,6 +48,8 @@ pub enum LineCap {
 pub trait Renderer {
     /// The font type used for rendering text. May be `()` if text rendering isn't supported.
     type Font;
+    /// The image type used for drawing images and textures. May be `()` if image drawing isn't supported.
+    type Image;
 
     /// Pushes the current transform matrix and clip region onto a stack.
     fn push(&mut self);
@@ -59,16 +83,96 @@ pub trait Renderer {
         color: Color,
         alignment: Alignment,
     ) -> f32;
+    /// Measures a single line of text, without drawing it. The width should match the advance returned by
+    /// [`Renderer::text`].
+    ///
+    /// The default implementation measures all text as zero-sized, for renderers that don't support text.
+    fn measure_text(&self, font: &Self::Font, text: &str) -> TextMetrics {
+        let _ = (font, text);
+        TextMetrics::default()
+    }
+
+    /// Draws the `source` region of the image, stretched to fill the provided rectangle. The source region is
+    /// expressed in the image's pixel coordinates. The image's colors are multiplied by the tint, so
+    /// [`Color::WHITE`] draws the image as is.
+    ///
+    /// The default implementation doesn't draw anything, for renderers that don't support images.
+    fn image(&mut self, rect: Rect, image: &Self::Image, source: Rect, tint: Color) {
+        let _ = (rect, image, source, tint);
+    }
+    /// Returns the size of the image, in pixels.
+    ///
+    /// The default implementation returns a size of zero, for renderers that don't support images.
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        let _ = image;
+        vector(0.0, 0.0)
+    }
+
+    /// Draws the image as a nine-slice (also known as nine-patch), filling the provided rectangle. The insets split
+    /// the image into nine parts, and are expressed in the image's pixels. The corners are drawn unscaled, the edges
+    /// are stretched along one axis, and the center is either stretched along both axes or tiled, depending on
+    /// `center`. If the rectangle is too small to fit the corners, they're scaled down.
+    ///
+    /// The default implementation draws each part using [`Renderer::image`]. Renderers that support drawing
+    /// nine-slices natively may override this.
+    fn nine_slice(
+        &mut self,
+        rect: Rect,
+        image: &Self::Image,
+        insets: Padding,
+        center: NineSliceCenter,
+        tint: Color,
+    ) {
+        let image_size = self.image_size(image);
+        for (rect, source) in nine_slice(rect, image_size, insets, center) {
+            self.image(rect, image, source, tint);
+        }
+    }
+}
+
+/// Returns the offset that should be applied to a line's points to make it pixel-perfect, as described in
+/// [`Renderer`]'s documentation. Lines with an odd thickness are centered on pixel centers, by moving them by half a
+/// pixel: down if they're horizontal, right if they're vertical, and along both axes if they're diagonal. Lines with an
+/// even thickness already land on pixel boundaries.
+#[cfg(any(feature = "software", feature = "svg"))]
+pub(crate) fn pixel_perfect_offset(a: Point, b: Point, thickness: f32) -> Vector {
+    let half_pixel = if thickness.round() as i64 % 2 == 1 {
+        0.5
+    } else {
+        0.0
+    };
+    vector(
+        if a.x == b.x || a.y != b.y {
+            half_pixel
+        } else {
+            0.0
+        },
+        if a.y == b.y || a.x != b.x {
+            half_pixel
+        } else {
+            0.0
+        },
+    )
 }
 
 /// A dummy renderer. This can be used for executing graphics commands without a graphical backend available.
+///
+/// Because it doesn't render text, all text measures as zero-sized.
 pub struct NoRenderer;
 
 /// A dummy font used by the NoRenderer backend.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct NoRendererFont;
 
+/// A dummy image used by the NoRenderer backend. It has no pixels, only a size.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NoRendererImage {
+    pub size: Vector,
+}
+
 impl Renderer for NoRenderer {
     type Font = NoRendererFont;
+    type Image = NoRendererImage;
 
     fn push(&mut self) {}
     fn pop(&mut self) {}
@@ -82,4 +186,10 @@ impl Renderer for NoRenderer {
     fn text(&mut self, _: Rect, _: &Self::Font, _: &str, _: Color, _: Alignment) -> f32 {
         0.0
     }
+
+    fn image(&mut self, _: Rect, _: &Self::Image, _: Rect, _: Color) {}
+
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        image.size
+    }
 }
@@ -0,0 +1,504 @@
+//! A CPU software rasterizer, rendering into an RGBA8 pixel buffer.
+//!
+//! This backend is available behind the `software` feature. It's mainly useful for running paws headlessly (eg. in
+//! tests), on machines without a GPU, and as a reference for how other renderers should behave.
+
+use std::sync::Arc;
+
+use crate::common::*;
+use crate::layout::*;
+use crate::renderer::*;
+
+/// An RGBA8 image that can be drawn by the [`SoftwareRenderer`].
+///
+/// The pixels are laid out the same way as in the renderer's pixel buffer, and are reference-counted, so cloning an
+/// image is cheap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftwareImage {
+    width: usize,
+    height: usize,
+    pixels: Arc<[u8]>,
+}
+
+impl SoftwareImage {
+    /// Creates a new image with the given size and RGBA8 pixels.
+    ///
+    /// # Panics
+    /// If the number of bytes doesn't match the size.
+    pub fn new(width: usize, height: usize, pixels: impl Into<Arc<[u8]>>) -> Self {
+        let pixels = pixels.into();
+        assert_eq!(
+            pixels.len(),
+            width * height * 4,
+            "image pixels don't match the image's size"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the color of the pixel at the given coordinates.
+    ///
+    /// # Panics
+    /// If the coordinates are out of bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) out of bounds",
+            x,
+            y
+        );
+        let i = (y * self.width + x) * 4;
+        Color::new(
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        )
+    }
+}
+
+/// A renderer that rasterizes shapes on the CPU into an RGBA8 pixel buffer.
+///
+/// All shapes are antialiased by computing their signed distance at every pixel's center. Pixels are laid out
+/// row by row, starting at the top-left corner, with 4 bytes per pixel in R, G, B, A order. Colors are stored
+/// as straight (non-premultiplied) alpha.
+///
+/// ## Pixel-perfect lines
+///
+/// As recommended by [`Renderer`]'s documentation, this renderer moves strokes by half a pixel, so that a line with
+/// an integer thickness lands exactly on pixel boundaries:
+///  - [`Renderer::line`] moves both points by half a pixel if the thickness is odd: horizontal lines are moved down,
+///    vertical lines are moved right, and diagonal lines are moved along both axes. This way a horizontal line from
+///    `(0, 0)` to `(10, 0)` with a thickness of 1 covers exactly the pixels `(0..10, 0)`. Lines with an even thickness
+///    already land on pixel boundaries, so they're left alone.
+///  - [`Renderer::outline`] strokes the rectangle inset by half of the thickness, such that the outline is always
+///    drawn inside of the rectangle, without spilling over to adjacent pixels.
+///
+/// ## Text
+///
+/// Text rendering is not supported, so the font type is `()`, [`Renderer::text`] doesn't draw anything, and all
+/// text measures as zero-sized.
+///
+/// ## Images
+///
+/// Images ([`SoftwareImage`]) are sampled using nearest-neighbor filtering, which keeps pixel art and icons drawn at
+/// integer scales crisp.
+pub struct SoftwareRenderer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    translation: Vector,
+    clip: Rect,
+    stack: Vec<(Vector, Rect)>,
+}
+
+impl SoftwareRenderer {
+    /// Creates a new software renderer with a pixel buffer of the given size, cleared to transparent black.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height * 4],
+            translation: vector(0.0, 0.0),
+            clip: Rect::new(point(0.0, 0.0), vector(width as f32, height as f32)),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns the width of the pixel buffer.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the pixel buffer.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the RGBA8 pixel buffer.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the RGBA8 pixel buffer, consuming the renderer.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Returns the color of the pixel at the given coordinates.
+    ///
+    /// # Panics
+    /// If the coordinates are out of bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({}, {}) out of bounds",
+            x,
+            y
+        );
+        let i = (y * self.width + x) * 4;
+        Color::new(
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        )
+    }
+
+    /// Fills the whole pixel buffer with the given color. This ignores the clip region and blending.
+    pub fn clear(&mut self, color: Color) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+    }
+
+    /// Blends the given color onto the pixel at the given coordinates, with the given coverage in the range [0; 1].
+    fn blend(&mut self, x: usize, y: usize, color: Color, coverage: f32) {
+        let i = (y * self.width + x) * 4;
+        let dst = &mut self.pixels[i..i + 4];
+        let src_a = color.a as f32 / 255.0 * coverage;
+        let dst_a = dst[3] as f32 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        if out_a <= 0.0 {
+            return;
+        }
+        let channel = |src: u8, dst: u8| {
+            let c = (src as f32 * src_a + dst as f32 * dst_a * (1.0 - src_a)) / out_a;
+            c.round().min(255.0) as u8
+        };
+        dst[0] = channel(color.r, dst[0]);
+        dst[1] = channel(color.g, dst[1]);
+        dst[2] = channel(color.b, dst[2]);
+        dst[3] = (out_a * 255.0).round() as u8;
+    }
+
+    /// Rasterizes a shape given by its bounding box (in absolute coordinates) and signed distance function.
+    /// The distance function receives pixel centers, and should return negative values inside of the shape.
+    fn rasterize(&mut self, bounds: Rect, color: Color, distance: impl Fn(Point) -> f32) {
+        self.shade(bounds, |p| (color, distance(p)));
+    }
+
+    /// Like [`SoftwareRenderer::rasterize`], but the color varies per pixel. The shader receives pixel centers, and
+    /// returns the color and signed distance at that pixel.
+    fn shade(&mut self, bounds: Rect, shader: impl Fn(Point) -> (Color, f32)) {
+        let bounds = bounds.sort();
+        let left = f32::max(bounds.left(), self.clip.left()).floor().max(0.0) as usize;
+        let top = f32::max(bounds.top(), self.clip.top()).floor().max(0.0) as usize;
+        let right = f32::min(bounds.right(), self.clip.right())
+            .ceil()
+            .min(self.width as f32);
+        let bottom = f32::min(bounds.bottom(), self.clip.bottom())
+            .ceil()
+            .min(self.height as f32);
+        if right <= 0.0 || bottom <= 0.0 {
+            return;
+        }
+        let (right, bottom) = (right as usize, bottom as usize);
+        for y in top..bottom {
+            for x in left..right {
+                let center = point(x as f32 + 0.5, y as f32 + 0.5);
+                if !self.clip.contains(center) {
+                    continue;
+                }
+                let (color, distance) = shader(center);
+                let coverage = (0.5 - distance).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+}
+
+/// Signed distance from a point to a rounded rectangle.
+fn rounded_rect_distance(p: Point, rect: Rect, radius: f32) -> f32 {
+    let half_size = rect.size / 2.0;
+    let radius = radius.max(0.0).min(half_size.x.min(half_size.y));
+    let d = p - rect.center();
+    let qx = d.x.abs() - half_size.x + radius;
+    let qy = d.y.abs() - half_size.y + radius;
+    let outside = vector(qx.max(0.0), qy.max(0.0)).length();
+    let inside = f32::min(f32::max(qx, qy), 0.0);
+    outside + inside - radius
+}
+
+/// Signed distance from a point to a line segment with the given thickness and cap.
+fn line_distance(p: Point, a: Point, b: Point, cap: LineCap, thickness: f32) -> f32 {
+    let half_thickness = thickness / 2.0;
+    let ab = b - a;
+    let length = ab.length();
+    if cap == LineCap::Round {
+        let t = if length == 0.0 {
+            0.0
+        } else {
+            (((p.x - a.x) * ab.x + (p.y - a.y) * ab.y) / (length * length)).clamp(0.0, 1.0)
+        };
+        return p.distance(a + ab * t) - half_thickness;
+    }
+    let direction = ab / length;
+    let ap = p - a;
+    // project the point onto the line's local coordinate system, centered at the midpoint
+    let along = ap.x * direction.x + ap.y * direction.y - length / 2.0;
+    let across = ap.x * -direction.y + ap.y * direction.x;
+    let half_length = match cap {
+        LineCap::Square => length / 2.0 + half_thickness,
+        _ => length / 2.0,
+    };
+    let qx = along.abs() - half_length;
+    let qy = across.abs() - half_thickness;
+    vector(qx.max(0.0), qy.max(0.0)).length() + f32::min(f32::max(qx, qy), 0.0)
+}
+
+impl Renderer for SoftwareRenderer {
+    type Font = ();
+    type Image = SoftwareImage;
+
+    fn push(&mut self) {
+        self.stack.push((self.translation, self.clip));
+    }
+
+    fn pop(&mut self) {
+        let (translation, clip) = self
+            .stack
+            .pop()
+            .expect("unbalanced push() and pop() on SoftwareRenderer");
+        self.translation = translation;
+        self.clip = clip;
+    }
+
+    fn translate(&mut self, vec: Vector) {
+        self.translation += vec;
+    }
+
+    fn clip(&mut self, rect: Rect) {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        self.clip = self.clip.intersection(rect);
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color, radius: f32) {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        self.rasterize(rect, color, |p| rounded_rect_distance(p, rect, radius));
+    }
+
+    fn outline(&mut self, rect: Rect, color: Color, radius: f32, thickness: f32) {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        let half_thickness = thickness / 2.0;
+        let stroke = Rect::new(
+            rect.position + vector(half_thickness, half_thickness),
+            rect.size - vector(thickness, thickness),
+        );
+        let radius = (radius - half_thickness).max(0.0);
+        self.rasterize(rect, color, |p| {
+            rounded_rect_distance(p, stroke, radius).abs() - half_thickness
+        });
+    }
+
+    fn line(&mut self, a: Point, b: Point, color: Color, cap: LineCap, thickness: f32) {
+        // zero-length lines only have a well-defined shape with round caps
+        if a == b && cap != LineCap::Round {
+            return;
+        }
+        let offset = pixel_perfect_offset(a, b, thickness);
+        let a = a + self.translation + offset;
+        let b = b + self.translation + offset;
+        let extent = thickness / 2.0 + 1.0;
+        let bounds = Rect::new(a, b - a).sort();
+        let bounds = Rect::new(
+            bounds.position - vector(extent, extent),
+            bounds.size + vector(extent * 2.0, extent * 2.0),
+        );
+        self.rasterize(bounds, color, |p| line_distance(p, a, b, cap, thickness));
+    }
+
+    fn text(&mut self, _: Rect, _: &Self::Font, _: &str, _: Color, _: Alignment) -> f32 {
+        0.0
+    }
+
+    fn image(&mut self, rect: Rect, image: &Self::Image, source: Rect, tint: Color) {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        if rect.width() <= 0.0 || rect.height() <= 0.0 || image.width == 0 || image.height == 0 {
+            return;
+        }
+        let scale = source.size / rect.size;
+        let tint_channel = |c: u8, t: u8| (c as u16 * t as u16 / 255) as u8;
+        self.shade(rect, |p| {
+            let uv = source.position + (p - rect.position) * scale;
+            let x = (uv.x.floor().max(0.0) as usize).min(image.width - 1);
+            let y = (uv.y.floor().max(0.0) as usize).min(image.height - 1);
+            let texel = image.pixel(x, y);
+            let color = Color::new(
+                tint_channel(texel.r, tint.r),
+                tint_channel(texel.g, tint.g),
+                tint_channel(texel.b, tint.b),
+                tint_channel(texel.a, tint.a),
+            );
+            (color, rounded_rect_distance(p, rect, 0.0))
+        });
+    }
+
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        vector(image.width as f32, image.height as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, Ui};
+
+    const RED: Color = rgb(255, 0, 0);
+
+    fn is_covered(renderer: &SoftwareRenderer, x: usize, y: usize) -> bool {
+        renderer.pixel(x, y).a != 0
+    }
+
+    #[test]
+    fn fill_is_pixel_perfect() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.fill(Rect::new((2.0, 3.0), (4.0, 5.0)), RED, 0.0);
+        for y in 0..16 {
+            for x in 0..16 {
+                let inside = (2..6).contains(&x) && (3..8).contains(&y);
+                assert_eq!(is_covered(&renderer, x, y), inside, "pixel ({}, {})", x, y);
+            }
+        }
+        assert_eq!(renderer.pixel(2, 3), RED);
+    }
+
+    #[test]
+    fn rounded_fill_cuts_corners() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.fill(Rect::new((0.0, 0.0), (16.0, 16.0)), RED, 8.0);
+        assert!(!is_covered(&renderer, 0, 0));
+        assert!(!is_covered(&renderer, 15, 15));
+        assert_eq!(renderer.pixel(8, 8), RED);
+        assert_eq!(renderer.pixel(1, 8), RED);
+    }
+
+    #[test]
+    fn outline_stays_inside() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.outline(Rect::new((2.0, 2.0), (8.0, 8.0)), RED, 0.0, 1.0);
+        assert_eq!(renderer.pixel(2, 2), RED);
+        assert_eq!(renderer.pixel(9, 9), RED);
+        assert_eq!(renderer.pixel(5, 2), RED);
+        assert!(!is_covered(&renderer, 1, 1));
+        assert!(!is_covered(&renderer, 10, 10));
+        assert!(!is_covered(&renderer, 5, 5));
+    }
+
+    #[test]
+    fn line_caps() {
+        let line = |cap| {
+            let mut renderer = SoftwareRenderer::new(16, 16);
+            renderer.line(point(4.0, 8.0), point(12.0, 8.0), RED, cap, 2.0);
+            renderer
+        };
+
+        let butt = line(LineCap::Butt);
+        assert!(is_covered(&butt, 4, 8));
+        assert!(is_covered(&butt, 11, 8));
+        assert!(!is_covered(&butt, 3, 8));
+        assert!(!is_covered(&butt, 12, 8));
+
+        let square = line(LineCap::Square);
+        assert_eq!(square.pixel(3, 8), RED);
+        assert_eq!(square.pixel(12, 8), RED);
+        assert!(!is_covered(&square, 2, 8));
+
+        let round = line(LineCap::Round);
+        assert!(is_covered(&round, 3, 8));
+        assert!(!is_covered(&round, 3, 6));
+        assert!(!is_covered(&round, 2, 8));
+    }
+
+    #[test]
+    fn thin_lines_are_sharp() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.line(point(0.0, 4.0), point(16.0, 4.0), RED, LineCap::Butt, 1.0);
+        renderer.line(point(4.0, 0.0), point(4.0, 16.0), RED, LineCap::Butt, 1.0);
+        for i in 0..16 {
+            assert_eq!(renderer.pixel(i, 4), RED);
+            assert_eq!(renderer.pixel(4, i), RED);
+            if i != 4 {
+                assert!(!is_covered(&renderer, i, 3));
+                assert!(!is_covered(&renderer, i, 5));
+                assert!(!is_covered(&renderer, 3, i));
+                assert!(!is_covered(&renderer, 5, i));
+            }
+        }
+    }
+
+    #[test]
+    fn translate_and_clip_stack() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.push();
+        renderer.translate(vector(4.0, 4.0));
+        renderer.clip(Rect::new((0.0, 0.0), (4.0, 4.0)));
+        renderer.fill(Rect::new((-4.0, -4.0), (16.0, 16.0)), RED, 0.0);
+        renderer.pop();
+        assert!(is_covered(&renderer, 4, 4));
+        assert!(is_covered(&renderer, 7, 7));
+        assert!(!is_covered(&renderer, 3, 3));
+        assert!(!is_covered(&renderer, 8, 8));
+
+        renderer.fill(Rect::new((0.0, 0.0), (1.0, 1.0)), RED, 0.0);
+        assert!(is_covered(&renderer, 0, 0));
+    }
+
+    #[test]
+    fn images_are_scaled_and_tinted() {
+        // a 2×1 image with a white and a red pixel
+        let image = SoftwareImage::new(2, 1, vec![255, 255, 255, 255, 255, 0, 0, 255]);
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.image(
+            Rect::new((0.0, 0.0), (8.0, 4.0)),
+            &image,
+            Rect::new((0.0, 0.0), (2.0, 1.0)),
+            Color::WHITE,
+        );
+        assert_eq!(renderer.pixel(0, 0), Color::WHITE);
+        assert_eq!(renderer.pixel(3, 3), Color::WHITE);
+        assert_eq!(renderer.pixel(4, 0), RED);
+        assert_eq!(renderer.pixel(7, 3), RED);
+        assert!(!is_covered(&renderer, 8, 0));
+        assert!(!is_covered(&renderer, 0, 4));
+
+        // only the red pixel, tinted green
+        renderer.image(
+            Rect::new((0.0, 8.0), (4.0, 4.0)),
+            &image,
+            Rect::new((1.0, 0.0), (1.0, 1.0)),
+            rgb(0, 255, 0),
+        );
+        assert_eq!(renderer.pixel(0, 8), Color::BLACK);
+    }
+
+    #[test]
+    fn ui_draws_into_groups() {
+        let mut ui = Ui::new(SoftwareRenderer::new(16, 16));
+        ui.root((16.0, 16.0), Layout::Horizontal);
+        ui.push((4.0, 16.0), Layout::Freeform);
+        ui.pop();
+        ui.push((4.0, 4.0), Layout::Freeform);
+        ui.fill(RED);
+        ui.pop();
+        assert!(!is_covered(ui.renderer(), 3, 0));
+        assert!(is_covered(ui.renderer(), 4, 0));
+        assert!(is_covered(ui.renderer(), 7, 3));
+        assert!(!is_covered(ui.renderer(), 8, 0));
+    }
+}
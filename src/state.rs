@@ -0,0 +1,51 @@
+//! Persistent per-widget state.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::id::*;
+
+struct Entry {
+    value: Box<dyn Any>,
+    /// Whether the entry was accessed since the last garbage collection.
+    touched: bool,
+}
+
+/// A typed map from widget IDs to state values. Entries that aren't accessed between two garbage collections are
+/// removed.
+#[derive(Default)]
+pub(crate) struct StateMap {
+    entries: HashMap<(Id, TypeId), Entry>,
+}
+
+impl StateMap {
+    /// Returns the state of type `S` for the given ID, inserting `S::default()` if there's none yet.
+    pub(crate) fn get_or_default<S: Any + Default>(&mut self, id: Id) -> &mut S {
+        let entry = self
+            .entries
+            .entry((id, TypeId::of::<S>()))
+            .or_insert_with(|| Entry {
+                value: Box::new(S::default()),
+                touched: false,
+            });
+        entry.touched = true;
+        entry
+            .value
+            .downcast_mut()
+            .expect("state entry has a mismatched type")
+    }
+
+    /// Removes all entries that weren't accessed since the last call to this function.
+    pub(crate) fn collect_garbage(&mut self) {
+        self.entries.retain(|_, entry| entry.touched);
+        for entry in self.entries.values_mut() {
+            entry.touched = false;
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
@@ -0,0 +1,405 @@
+//! An SVG export renderer.
+//!
+//! This backend is available behind the `svg` feature. It's useful for producing screenshots of UIs without a GPU,
+//! as the resulting images can be viewed in any web browser.
+
+use std::fmt::Write;
+
+use crate::common::*;
+use crate::layout::*;
+use crate::renderer::*;
+
+/// The font used by the [`SvgRenderer`]. This maps directly onto the `font-family` and `font-size` attributes of the
+/// `<text>` element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgFont {
+    pub family: String,
+    pub size: f32,
+}
+
+impl SvgFont {
+    /// Creates a new font with the given CSS font family and size.
+    pub fn new(family: impl Into<String>, size: f32) -> Self {
+        Self {
+            family: family.into(),
+            size,
+        }
+    }
+}
+
+/// An image used by the [`SvgRenderer`]. The image is referenced by URL (which may also be a `data:` URL) through the
+/// `href` attribute of the `<image>` element. Because the renderer can't load the image, its size in pixels must be
+/// provided upfront.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgImage {
+    pub href: String,
+    pub size: Vector,
+}
+
+impl SvgImage {
+    /// Creates a new image with the given URL and size.
+    pub fn new(href: impl Into<String>, size: impl Into<Vector>) -> Self {
+        Self {
+            href: href.into(),
+            size: size.into(),
+        }
+    }
+}
+
+/// A renderer that outputs an SVG document.
+///
+/// The transform and clip stack maps onto nested `<g>` elements: [`Renderer::translate`] opens a
+/// `<g transform="translate(x y)">`, and [`Renderer::clip`] defines a `<clipPath>` and opens a `<g clip-path>` that
+/// references it. All groups opened since the last [`Renderer::push`] are closed by the matching
+/// [`Renderer::pop`].
+///
+/// Lines and outlines follow the same pixel-perfect conventions as the software renderer: lines with an odd thickness
+/// are moved by half a pixel, and outlines are stroked on the inside of the rectangle.
+///
+/// Because SVG text is laid out by the viewer, [`Renderer::measure_text`] can only estimate the metrics of text: every
+/// character is assumed to be [`SvgRenderer::CHARACTER_WIDTH`] times the font size wide, and the line height is
+/// [`SvgRenderer::LINE_HEIGHT`] times the font size, which matches the `normal` line height of most fonts. The
+/// horizontal advance returned by [`Renderer::text`] is the same estimate.
+///
+/// Images are drawn using nested `<svg>` elements, whose view box selects the source region. SVG has no way of
+/// multiplying an image by a color, so only the alpha of the tint is applied, as the image's opacity.
+///
+/// Once drawing is done, the document can be obtained using [`SvgRenderer::document`].
+pub struct SvgRenderer {
+    size: Vector,
+    body: String,
+    open_groups: usize,
+    stack: Vec<usize>,
+    clip_paths: usize,
+}
+
+impl SvgRenderer {
+    /// The estimated width of a single character, relative to the font size.
+    pub const CHARACTER_WIDTH: f32 = 0.6;
+    /// The estimated line height, relative to the font size.
+    pub const LINE_HEIGHT: f32 = 1.2;
+
+    /// Creates a new SVG renderer producing an image of the given size.
+    pub fn new(size: impl Into<Vector>) -> Self {
+        Self {
+            size: size.into(),
+            body: String::new(),
+            open_groups: 0,
+            stack: Vec::new(),
+            clip_paths: 0,
+        }
+    }
+
+    /// Returns the size of the image.
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// Clears everything drawn so far, resetting the renderer to its initial state.
+    pub fn clear(&mut self) {
+        self.body.clear();
+        self.open_groups = 0;
+        self.stack.clear();
+        self.clip_paths = 0;
+    }
+
+    /// Returns the complete SVG document. Groups that were left open by unbalanced `push()`es are closed.
+    pub fn document(&self) -> String {
+        let mut document = String::new();
+        let _ = writeln!(
+            document,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.size.x,
+            h = self.size.y,
+        );
+        document.push_str(&self.body);
+        for _ in 0..self.open_groups {
+            document.push_str("</g>\n");
+        }
+        document.push_str("</svg>\n");
+        document
+    }
+}
+
+/// Formats a color as SVG paint attributes with the given prefix (`fill` or `stroke`).
+fn paint(prefix: &str, color: Color) -> String {
+    let mut paint = format!(
+        r##"{}="#{:02x}{:02x}{:02x}""##,
+        prefix, color.r, color.g, color.b
+    );
+    if color.a != 255 {
+        let _ = write!(paint, r#" {}-opacity="{}""#, prefix, color.a as f32 / 255.0);
+    }
+    paint
+}
+
+/// Escapes the given text for use inside of XML text content and attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Renderer for SvgRenderer {
+    type Font = SvgFont;
+    type Image = SvgImage;
+
+    fn push(&mut self) {
+        self.stack.push(self.open_groups);
+    }
+
+    fn pop(&mut self) {
+        let open_groups = self
+            .stack
+            .pop()
+            .expect("unbalanced push() and pop() on SvgRenderer");
+        for _ in open_groups..self.open_groups {
+            self.body.push_str("</g>\n");
+        }
+        self.open_groups = open_groups;
+    }
+
+    fn translate(&mut self, vec: Vector) {
+        let _ = writeln!(
+            self.body,
+            r#"<g transform="translate({} {})">"#,
+            vec.x, vec.y
+        );
+        self.open_groups += 1;
+    }
+
+    fn clip(&mut self, rect: Rect) {
+        let rect = rect.sort();
+        let id = self.clip_paths;
+        self.clip_paths += 1;
+        let _ = writeln!(
+            self.body,
+            r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            id,
+            rect.x(),
+            rect.y(),
+            rect.width(),
+            rect.height()
+        );
+        let _ = writeln!(self.body, r#"<g clip-path="url(#clip{})">"#, id);
+        self.open_groups += 1;
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color, radius: f32) {
+        let rect = rect.sort();
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {}/>"#,
+            rect.x(),
+            rect.y(),
+            rect.width(),
+            rect.height(),
+            radius,
+            paint("fill", color)
+        );
+    }
+
+    fn outline(&mut self, rect: Rect, color: Color, radius: f32, thickness: f32) {
+        let rect = rect.sort();
+        let half_thickness = thickness / 2.0;
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="none" {} stroke-width="{}"/>"#,
+            rect.x() + half_thickness,
+            rect.y() + half_thickness,
+            rect.width() - thickness,
+            rect.height() - thickness,
+            (radius - half_thickness).max(0.0),
+            paint("stroke", color),
+            thickness
+        );
+    }
+
+    fn line(&mut self, a: Point, b: Point, color: Color, cap: LineCap, thickness: f32) {
+        let offset = pixel_perfect_offset(a, b, thickness);
+        let (a, b) = (a + offset, b + offset);
+        let cap = match cap {
+            LineCap::Butt => "butt",
+            LineCap::Square => "square",
+            LineCap::Round => "round",
+        };
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {} stroke-width="{}" stroke-linecap="{}"/>"#,
+            a.x,
+            a.y,
+            b.x,
+            b.y,
+            paint("stroke", color),
+            thickness,
+            cap
+        );
+    }
+
+    fn text(
+        &mut self,
+        rect: Rect,
+        font: &Self::Font,
+        text: &str,
+        color: Color,
+        alignment: Alignment,
+    ) -> f32 {
+        let (x, anchor) = match alignment.0 {
+            Left => (rect.left(), "start"),
+            Center => (rect.center_x(), "middle"),
+            Right => (rect.right(), "end"),
+        };
+        let (y, baseline) = match alignment.1 {
+            Top => (rect.top(), "text-before-edge"),
+            Middle => (rect.center_y(), "central"),
+            Bottom => (rect.bottom(), "text-after-edge"),
+        };
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{}" y="{}" text-anchor="{}" dominant-baseline="{}" font-family="{}" font-size="{}" {}>{}</text>"#,
+            x,
+            y,
+            anchor,
+            baseline,
+            escape(&font.family),
+            font.size,
+            paint("fill", color),
+            escape(text)
+        );
+        self.measure_text(font, text).width
+    }
+
+    fn measure_text(&self, font: &Self::Font, text: &str) -> TextMetrics {
+        // the leading is split evenly between the top and bottom of the line
+        let leading = (Self::LINE_HEIGHT - 1.0) * font.size;
+        TextMetrics {
+            width: text.chars().count() as f32 * Self::CHARACTER_WIDTH * font.size,
+            ascent: 0.8 * font.size + leading / 2.0,
+            descent: 0.2 * font.size + leading / 2.0,
+            line_height: Self::LINE_HEIGHT * font.size,
+        }
+    }
+
+    fn image(&mut self, rect: Rect, image: &Self::Image, source: Rect, tint: Color) {
+        let rect = rect.sort();
+        let _ = writeln!(
+            self.body,
+            r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="none"><image width="{}" height="{}" href="{}" opacity="{}"/></svg>"#,
+            rect.x(),
+            rect.y(),
+            rect.width(),
+            rect.height(),
+            source.x(),
+            source.y(),
+            source.width(),
+            source.height(),
+            image.size.x,
+            image.size.y,
+            escape(&image.href),
+            tint.a as f32 / 255.0
+        );
+    }
+
+    fn image_size(&self, image: &Self::Image) -> Vector {
+        image.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ui;
+
+    #[test]
+    fn stack_maps_to_groups() {
+        let mut ui = Ui::new(SvgRenderer::new((100.0, 100.0)));
+        ui.root((100.0, 100.0), Layout::Vertical);
+        ui.push((50.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((50.0, 20.0), Layout::Freeform);
+        ui.draw(|ui| {
+            ui.render().clip(Rect::new((0.0, 0.0), (50.0, 20.0)));
+            ui.render()
+                .fill(Rect::new((0.0, 0.0), (50.0, 20.0)), Color::WHITE, 2.0);
+        });
+        ui.pop();
+
+        assert_eq!(
+            ui.renderer().document(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100" viewBox="0 0 100 100">
+<g transform="translate(0 20)">
+<clipPath id="clip0"><rect x="0" y="0" width="50" height="20"/></clipPath>
+<g clip-path="url(#clip0)">
+<rect x="0" y="0" width="50" height="20" rx="2" fill="#ffffff"/>
+</g>
+</g>
+</svg>
+"##
+        );
+    }
+
+    #[test]
+    fn lines_and_text() {
+        let mut svg = SvgRenderer::new((100.0, 100.0));
+        svg.line(
+            point(0.0, 10.0),
+            point(100.0, 10.0),
+            rgba(255, 0, 0, 51),
+            LineCap::Round,
+            1.0,
+        );
+        svg.text(
+            Rect::new((0.0, 0.0), (100.0, 100.0)),
+            &SvgFont::new("sans-serif", 14.0),
+            "a < b",
+            Color::BLACK,
+            (Right, Bottom),
+        );
+        let document = svg.document();
+        assert!(document.contains(
+            r##"<line x1="0" y1="10.5" x2="100" y2="10.5" stroke="#ff0000" stroke-opacity="0.2" stroke-width="1" stroke-linecap="round"/>"##
+        ));
+        assert!(document.contains(
+            r#"<text x="100" y="100" text-anchor="end" dominant-baseline="text-after-edge""#
+        ));
+        assert!(document.contains(">a &lt; b</text>"));
+    }
+
+    #[test]
+    fn text_advance_matches_measurement() {
+        let mut svg = SvgRenderer::new((100.0, 100.0));
+        let font = SvgFont::new("sans-serif", 14.0);
+        let advance = svg.text(
+            Rect::new((0.0, 0.0), (100.0, 100.0)),
+            &font,
+            "hello",
+            Color::BLACK,
+            (Left, Top),
+        );
+        assert!(advance > 0.0);
+        assert_eq!(advance, svg.measure_text(&font, "hello").width);
+    }
+
+    #[test]
+    fn images_select_source_region() {
+        let mut svg = SvgRenderer::new((100.0, 100.0));
+        svg.image(
+            Rect::new((10.0, 20.0), (40.0, 40.0)),
+            &SvgImage::new("icons.png?a&b", (64.0, 32.0)),
+            Rect::new((32.0, 0.0), (32.0, 32.0)),
+            rgba(255, 255, 255, 51),
+        );
+        assert!(svg.document().contains(
+            r#"<svg x="10" y="20" width="40" height="40" viewBox="32 0 32 32" preserveAspectRatio="none"><image width="64" height="32" href="icons.png?a&amp;b" opacity="0.2"/></svg>"#
+        ));
+    }
+}
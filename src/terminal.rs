@@ -0,0 +1,615 @@
+//! A terminal renderer, rasterizing into a grid of character cells and outputting ANSI escape sequences.
+//!
+//! This backend is available behind the `terminal` feature. It's useful for building text user interfaces, eg. for
+//! tools that are meant to be used over SSH.
+
+use std::fmt::Write;
+
+use crate::common::*;
+use crate::layout::*;
+use crate::renderer::*;
+
+/// A single character cell in the terminal grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    /// The foreground color. A fully transparent color means the terminal's default foreground color.
+    pub foreground: Color,
+    /// The background color. A fully transparent color means the terminal's default background color.
+    pub background: Color,
+}
+
+impl Default for Cell {
+    /// The default cell is an empty space with the terminal's default colors.
+    fn default() -> Self {
+        Self {
+            character: ' ',
+            foreground: Color::TRANSPARENT,
+            background: Color::TRANSPARENT,
+        }
+    }
+}
+
+/// A renderer that rasterizes into a grid of character cells, which can then be output to a terminal supporting
+/// ANSI truecolor escape sequences.
+///
+/// Coordinates are expressed in paws units, as usual; the size of a single cell in these units is configurable, so that
+/// the same UI code can be used both for graphical and terminal backends. A cell is considered to be covered by a
+/// shape if its center lies inside of the shape.
+///
+/// The primitives are mapped onto cells like so:
+///  - [`Renderer::fill`] sets the background color of the covered cells. The corner radius is ignored.
+///  - [`Renderer::outline`] draws a box using box-drawing characters along the edge cells of the rectangle, in the
+///    given color. Boxes with a non-zero corner radius get rounded corners.
+///  - [`Renderer::line`] draws a line using box-drawing characters, occupying the row (or column) that contains the
+///    line. Lines that cross each other or an outline are joined together. Square and round caps extend the line by
+///    half of its thickness; apart from that, the thickness is ignored.
+///  - [`Renderer::text`] writes the text into cells, one character per cell, aligned inside of the cells covered by
+///    the rectangle. Text is not clipped to the rectangle, only to the clip region.
+///
+/// Text is not rendered with any particular font, so the font type is `()`. Every character of the text is measured
+/// as a single cell, sitting entirely above the baseline. Images can't be displayed in character
+/// cells, so the image type is `()` as well; [`Renderer::image`] doesn't draw anything, and all images have a size
+/// of zero.
+///
+/// A frame should be started with [`TerminalRenderer::clear`], and ended with [`TerminalRenderer::present`], which
+/// returns the escape sequences needed to update the terminal. Only the cells that changed since the last presented
+/// frame are output.
+pub struct TerminalRenderer {
+    columns: usize,
+    rows: usize,
+    cell_size: Vector,
+    cells: Vec<Cell>,
+    presented: Option<Vec<Cell>>,
+    translation: Vector,
+    clip: Rect,
+    stack: Vec<(Vector, Rect)>,
+}
+
+impl TerminalRenderer {
+    /// Creates a new terminal renderer with a grid of the given size, where each cell spans `cell_size` paws units.
+    pub fn new(columns: usize, rows: usize, cell_size: impl Into<Vector>) -> Self {
+        let cell_size = cell_size.into();
+        Self {
+            columns,
+            rows,
+            cell_size,
+            cells: vec![Cell::default(); columns * rows],
+            presented: None,
+            translation: vector(0.0, 0.0),
+            clip: Self::full_clip(columns, rows, cell_size),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns the number of columns in the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the size of a single cell, in paws units.
+    pub fn cell_size(&self) -> Vector {
+        self.cell_size
+    }
+
+    /// Returns the size of the whole grid, in paws units. This is what should be used as the root group's size.
+    pub fn size(&self) -> Vector {
+        vector(self.columns as f32, self.rows as f32) * self.cell_size
+    }
+
+    /// Returns the cell at the given column and row.
+    ///
+    /// # Panics
+    /// If the coordinates are out of bounds.
+    pub fn cell(&self, column: usize, row: usize) -> Cell {
+        assert!(
+            column < self.columns && row < self.rows,
+            "cell ({}, {}) out of bounds",
+            column,
+            row
+        );
+        self.cells[row * self.columns + column]
+    }
+
+    /// Resizes the grid, clearing all cells. The next call to [`TerminalRenderer::present`] redraws the whole screen.
+    pub fn resize(&mut self, columns: usize, rows: usize) {
+        self.columns = columns;
+        self.rows = rows;
+        self.cells = vec![Cell::default(); columns * rows];
+        self.presented = None;
+        self.clip = Self::full_clip(columns, rows, self.cell_size);
+    }
+
+    /// Clears all cells to their default state. This should be called at the start of every frame.
+    pub fn clear(&mut self) {
+        for cell in &mut self.cells {
+            *cell = Cell::default();
+        }
+    }
+
+    /// Forces the next call to [`TerminalRenderer::present`] to redraw the whole screen, eg. after the terminal's
+    /// contents were overwritten by something else.
+    pub fn invalidate(&mut self) {
+        self.presented = None;
+    }
+
+    /// Returns the escape sequences that update the terminal to display the current frame. Only cells that changed
+    /// since the previous call are output, unless the screen was invalidated.
+    pub fn present(&mut self) -> String {
+        let mut output = String::new();
+        if self.presented.is_none() {
+            output.push_str("\x1b[0m\x1b[2J");
+        }
+        // the last written cell and colors, used to avoid emitting redundant escape sequences
+        let mut cursor = None;
+        let mut colors = None;
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let index = row * self.columns + column;
+                let cell = self.cells[index];
+                if let Some(presented) = &self.presented {
+                    if presented[index] == cell {
+                        continue;
+                    }
+                }
+                if cursor != Some((column, row)) {
+                    let _ = write!(output, "\x1b[{};{}H", row + 1, column + 1);
+                }
+                if colors != Some((cell.foreground, cell.background)) {
+                    output.push_str("\x1b[");
+                    sgr_color(&mut output, 38, cell.foreground);
+                    output.push(';');
+                    sgr_color(&mut output, 48, cell.background);
+                    output.push('m');
+                    colors = Some((cell.foreground, cell.background));
+                }
+                output.push(cell.character);
+                cursor = Some((column + 1, row));
+            }
+        }
+        if colors.is_some() {
+            output.push_str("\x1b[0m");
+        }
+        self.presented = Some(self.cells.clone());
+        output
+    }
+
+    fn full_clip(columns: usize, rows: usize, cell_size: Vector) -> Rect {
+        Rect::new(
+            point(0.0, 0.0),
+            vector(columns as f32, rows as f32) * cell_size,
+        )
+    }
+
+    /// Returns the range of columns and rows whose cell centers lie inside of the given rectangle, which is expressed
+    /// in absolute coordinates. The ranges are also limited to the clip region and the grid's bounds.
+    fn cell_range(&self, rect: Rect) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let rect = rect.sort();
+        let left = f32::max(rect.left(), self.clip.left());
+        let top = f32::max(rect.top(), self.clip.top());
+        let right = f32::min(rect.right(), self.clip.right());
+        let bottom = f32::min(rect.bottom(), self.clip.bottom());
+        let first = |start: f32, size: f32| (start / size - 0.5).ceil().max(0.0) as usize;
+        let end = |end: f32, size: f32, count: usize| {
+            ((end / size - 0.5).ceil().max(0.0) as usize).min(count)
+        };
+        (
+            first(left, self.cell_size.x)..end(right, self.cell_size.x, self.columns),
+            first(top, self.cell_size.y)..end(bottom, self.cell_size.y, self.rows),
+        )
+    }
+
+    /// Returns the cell at the given column and row, if it's inside of the grid and the clip region.
+    fn cell_mut(&mut self, column: isize, row: isize) -> Option<&mut Cell> {
+        if column < 0 || row < 0 || column as usize >= self.columns || row as usize >= self.rows {
+            return None;
+        }
+        let center = (vector(column as f32, row as f32) + vector(0.5, 0.5)) * self.cell_size;
+        if !self.clip.contains(center) {
+            return None;
+        }
+        Some(&mut self.cells[row as usize * self.columns + column as usize])
+    }
+
+    /// Returns the connections a line spanning the given range of cells should have at the given cell, which is
+    /// specified as its position along the line and across it. The ends of the line only connect inwards if they're
+    /// joined onto an existing box-drawing character, such that a line ending on a box's edge forms a T junction.
+    fn line_connections(
+        &self,
+        along: isize,
+        across: isize,
+        range: &std::ops::Range<isize>,
+        backward: u8,
+        forward: u8,
+    ) -> u8 {
+        let (column, row) = if backward == LEFT {
+            (along, across)
+        } else {
+            (across, along)
+        };
+        let existing = if column >= 0
+            && row >= 0
+            && (column as usize) < self.columns
+            && (row as usize) < self.rows
+        {
+            box_connections(self.cells[row as usize * self.columns + column as usize].character)
+        } else {
+            0
+        };
+        let mut connections = backward | forward;
+        if existing != 0 && along == range.start {
+            connections &= !backward;
+        }
+        if existing != 0 && along == range.end - 1 {
+            connections &= !forward;
+        }
+        connections
+    }
+
+    /// Joins the given box-drawing connections into the cell at the given column and row.
+    fn draw_box(
+        &mut self,
+        column: isize,
+        row: isize,
+        connections: u8,
+        color: Color,
+        rounded: bool,
+    ) {
+        if let Some(cell) = self.cell_mut(column, row) {
+            let existing = box_connections(cell.character);
+            cell.character = if rounded && existing == 0 {
+                rounded_box_character(connections)
+            } else {
+                box_character(existing | connections)
+            };
+            cell.foreground = color;
+        }
+    }
+}
+
+/// Appends an SGR color parameter with the given base (38 for foreground, 48 for background) to the output.
+fn sgr_color(output: &mut String, base: u8, color: Color) {
+    if color.a == 0 {
+        let _ = write!(output, "{}", base + 1);
+    } else {
+        let _ = write!(output, "{};2;{};{};{}", base, color.r, color.g, color.b);
+    }
+}
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// Returns the connections of a light box-drawing character, or 0 if the character isn't one.
+fn box_connections(character: char) -> u8 {
+    match character {
+        '╵' => UP,
+        '╷' => DOWN,
+        '╴' => LEFT,
+        '╶' => RIGHT,
+        '│' => UP | DOWN,
+        '─' => LEFT | RIGHT,
+        '┌' | '╭' => DOWN | RIGHT,
+        '┐' | '╮' => DOWN | LEFT,
+        '└' | '╰' => UP | RIGHT,
+        '┘' | '╯' => UP | LEFT,
+        '├' => UP | DOWN | RIGHT,
+        '┤' => UP | DOWN | LEFT,
+        '┬' => DOWN | LEFT | RIGHT,
+        '┴' => UP | LEFT | RIGHT,
+        '┼' => UP | DOWN | LEFT | RIGHT,
+        _ => 0,
+    }
+}
+
+/// Returns the light box-drawing character with the given connections.
+fn box_character(connections: u8) -> char {
+    const CHARACTERS: [char; 16] = [
+        ' ', '╵', '╷', '│', '╴', '┘', '┐', '┤', '╶', '└', '┌', '├', '─', '┴', '┬', '┼',
+    ];
+    CHARACTERS[connections as usize & 0xF]
+}
+
+/// Returns the box-drawing character with the given connections, with rounded corners if it's a corner.
+fn rounded_box_character(connections: u8) -> char {
+    match connections {
+        c if c == DOWN | RIGHT => '╭',
+        c if c == DOWN | LEFT => '╮',
+        c if c == UP | RIGHT => '╰',
+        c if c == UP | LEFT => '╯',
+        c => box_character(c),
+    }
+}
+
+/// Blends the source color over the destination color. Fully transparent destinations (the terminal's default color)
+/// are treated as if the source color was drawn over black.
+fn blend(dst: Color, src: Color) -> Color {
+    if src.a == 255 {
+        return src;
+    }
+    let a = src.a as f32 / 255.0;
+    let mix = |s: u8, d: u8| (s as f32 * a + d as f32 * (1.0 - a)).round() as u8;
+    rgb(mix(src.r, dst.r), mix(src.g, dst.g), mix(src.b, dst.b))
+}
+
+impl Renderer for TerminalRenderer {
+    type Font = ();
+    type Image = ();
+
+    fn push(&mut self) {
+        self.stack.push((self.translation, self.clip));
+    }
+
+    fn pop(&mut self) {
+        let (translation, clip) = self
+            .stack
+            .pop()
+            .expect("unbalanced push() and pop() on TerminalRenderer");
+        self.translation = translation;
+        self.clip = clip;
+    }
+
+    fn translate(&mut self, vec: Vector) {
+        self.translation += vec;
+    }
+
+    fn clip(&mut self, rect: Rect) {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        self.clip = self.clip.intersection(rect);
+    }
+
+    fn fill(&mut self, rect: Rect, color: Color, _radius: f32) {
+        if color.a == 0 {
+            return;
+        }
+        let rect = Rect::new(rect.position + self.translation, rect.size);
+        let (columns, rows) = self.cell_range(rect);
+        for row in rows {
+            for column in columns.clone() {
+                let cell = &mut self.cells[row * self.columns + column];
+                cell.background = blend(cell.background, color);
+            }
+        }
+    }
+
+    fn outline(&mut self, rect: Rect, color: Color, radius: f32, _thickness: f32) {
+        let rect = Rect::new(rect.position + self.translation, rect.size);
+        // the clip region is applied per cell by draw_box(), so that boxes that are only partially visible still get
+        // the correct characters on their edges
+        let clip = std::mem::replace(
+            &mut self.clip,
+            Self::full_clip(self.columns, self.rows, self.cell_size),
+        );
+        let (columns, rows) = self.cell_range(rect);
+        self.clip = clip;
+        if columns.is_empty() || rows.is_empty() {
+            return;
+        }
+        let (first_column, last_column) = (columns.start, columns.end - 1);
+        let (first_row, last_row) = (rows.start, rows.end - 1);
+        let rounded = radius > 0.0;
+        for row in rows {
+            for column in columns.clone() {
+                let horizontal_edge = row == first_row || row == last_row;
+                let vertical_edge = column == first_column || column == last_column;
+                let mut connections = 0;
+                if horizontal_edge && column > first_column {
+                    connections |= LEFT;
+                }
+                if horizontal_edge && column < last_column {
+                    connections |= RIGHT;
+                }
+                if vertical_edge && row > first_row {
+                    connections |= UP;
+                }
+                if vertical_edge && row < last_row {
+                    connections |= DOWN;
+                }
+                if connections != 0 {
+                    self.draw_box(column as isize, row as isize, connections, color, rounded);
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, a: Point, b: Point, color: Color, cap: LineCap, thickness: f32) {
+        let extension = match cap {
+            LineCap::Butt => 0.0,
+            LineCap::Square | LineCap::Round => thickness / 2.0,
+        };
+        let (a, b) = (a + self.translation, b + self.translation);
+        let cell_size = self.cell_size;
+        // the cell containing the given coordinate
+        let cell = |x: f32, size: f32| (x / size).floor() as isize;
+        // the range of cells whose centers lie on the given span
+        let span = |from: f32, to: f32, size: f32| {
+            let (from, to) = (
+                f32::min(from, to) - extension,
+                f32::max(from, to) + extension,
+            );
+            (from / size - 0.5).ceil() as isize..(to / size - 0.5).ceil() as isize
+        };
+        if a.y == b.y {
+            let row = cell(a.y, cell_size.y);
+            let columns = span(a.x, b.x, cell_size.x);
+            for column in columns.clone() {
+                let connections = self.line_connections(column, row, &columns, LEFT, RIGHT);
+                self.draw_box(column, row, connections, color, false);
+            }
+        } else if a.x == b.x {
+            let column = cell(a.x, cell_size.x);
+            let rows = span(a.y, b.y, cell_size.y);
+            for row in rows.clone() {
+                let connections = self.line_connections(row, column, &rows, UP, DOWN);
+                self.draw_box(column, row, connections, color, false);
+            }
+        } else {
+            // diagonal lines are stepped along their major axis, one cell at a time
+            let (a, b) = (a / cell_size, b / cell_size);
+            let delta = b - a;
+            let steps = f32::max(delta.x.abs(), delta.y.abs()).ceil().max(1.0) as usize;
+            let character = if (delta.x > 0.0) == (delta.y > 0.0) {
+                '╲'
+            } else {
+                '╱'
+            };
+            for step in 0..=steps {
+                let p = a + delta * (step as f32 / steps as f32);
+                if let Some(cell) = self.cell_mut(p.x.floor() as isize, p.y.floor() as isize) {
+                    cell.character = character;
+                    cell.foreground = color;
+                }
+            }
+        }
+    }
+
+    fn text(
+        &mut self,
+        rect: Rect,
+        _font: &Self::Font,
+        text: &str,
+        color: Color,
+        alignment: Alignment,
+    ) -> f32 {
+        let rect = Rect::new(rect.position + self.translation, rect.size).sort();
+        let length = text.chars().count() as isize;
+        let first = |start: f32, size: f32| (start / size - 0.5).ceil() as isize;
+        let columns = first(rect.left(), self.cell_size.x)..first(rect.right(), self.cell_size.x);
+        let rows = first(rect.top(), self.cell_size.y)..first(rect.bottom(), self.cell_size.y);
+        let column = match alignment.0 {
+            Left => columns.start,
+            Center => columns.start + (columns.end - columns.start - length) / 2,
+            Right => columns.end - length,
+        };
+        let row = match alignment.1 {
+            Top => rows.start,
+            Middle => rows.start + (rows.end - rows.start - 1) / 2,
+            Bottom => rows.end - 1,
+        };
+        for (i, character) in text.chars().enumerate() {
+            if let Some(cell) = self.cell_mut(column + i as isize, row) {
+                cell.character = character;
+                cell.foreground = color;
+            }
+        }
+        length as f32 * self.cell_size.x
+    }
+
+    fn measure_text(&self, _: &Self::Font, text: &str) -> TextMetrics {
+        TextMetrics {
+            width: text.chars().count() as f32 * self.cell_size.x,
+            ascent: self.cell_size.y,
+            descent: 0.0,
+            line_height: self.cell_size.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TextOptions, TextOverflow, Ui};
+
+    fn row(renderer: &TerminalRenderer, row: usize) -> String {
+        (0..renderer.columns())
+            .map(|column| renderer.cell(column, row).character)
+            .collect()
+    }
+
+    #[test]
+    fn outlines_and_borders_join() {
+        let mut ui = Ui::new(TerminalRenderer::new(6, 4, (2.0, 4.0)));
+        ui.root((12.0, 16.0), Layout::Freeform);
+        ui.outline(Color::WHITE, 1.0);
+        ui.push((12.0, 8.0), Layout::Freeform);
+        ui.border_bottom(Color::WHITE, 1.0);
+        ui.pop();
+
+        assert_eq!(row(ui.renderer(), 0), "┌────┐");
+        assert_eq!(row(ui.renderer(), 1), "│    │");
+        assert_eq!(row(ui.renderer(), 2), "├────┤");
+        assert_eq!(row(ui.renderer(), 3), "└────┘");
+    }
+
+    #[test]
+    fn fill_and_aligned_text() {
+        let blue = rgb(0, 0, 255);
+        let mut ui = Ui::new(TerminalRenderer::new(8, 3, (1.0, 1.0)));
+        ui.root((8.0, 3.0), Layout::Freeform);
+        ui.fill(blue);
+        ui.text(&(), "hi", Color::WHITE, CENTER);
+        ui.text(&(), "ab", Color::WHITE, (Right, Bottom));
+
+        assert_eq!(row(ui.renderer(), 1), "   hi   ");
+        assert_eq!(row(ui.renderer(), 2), "      ab");
+        assert_eq!(ui.renderer().cell(0, 0).background, blue);
+        assert_eq!(ui.renderer().cell(3, 1).foreground, Color::WHITE);
+        assert_eq!(ui.renderer().cell(3, 1).background, blue);
+    }
+
+    #[test]
+    fn text_sized_groups() {
+        let mut ui = Ui::new(TerminalRenderer::new(16, 3, (1.0, 1.0)));
+        ui.root((16.0, 3.0), Layout::Horizontal);
+        for label in &["ok", "cancel"] {
+            ui.push_text(&(), label, 1.0, Layout::Freeform);
+            ui.outline(Color::WHITE, 1.0);
+            ui.text(&(), label, Color::WHITE, CENTER);
+            ui.pop();
+        }
+        assert_eq!(row(ui.renderer(), 0), "┌──┐┌──────┐    ");
+        assert_eq!(row(ui.renderer(), 1), "│ok││cancel│    ");
+        assert_eq!(row(ui.renderer(), 2), "└──┘└──────┘    ");
+    }
+
+    #[test]
+    fn paragraphs() {
+        let mut ui = Ui::new(TerminalRenderer::new(8, 3, (1.0, 1.0)));
+        ui.root((8.0, 3.0), Layout::Freeform);
+        let options = TextOptions::new((Center, Top)).overflow(TextOverflow::Ellipsis);
+        let layout = ui.paragraph(&(), "wrapped text in a small box", Color::WHITE, options);
+        assert!(layout.truncated);
+        assert_eq!(layout.size, vector(8.0, 3.0));
+        assert_eq!(row(ui.renderer(), 0), "wrapped ");
+        assert_eq!(row(ui.renderer(), 1), "text in ");
+        assert_eq!(row(ui.renderer(), 2), "a small…");
+        assert_eq!(
+            ui.paragraph_size(&(), "wrapped text in a small box", 8.0, options),
+            vector(7.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn present_only_outputs_changes() {
+        let mut terminal = TerminalRenderer::new(4, 2, (1.0, 1.0));
+        terminal.text(
+            Rect::new((0.0, 0.0), (4.0, 1.0)),
+            &(),
+            "ab",
+            Color::WHITE,
+            (Left, Top),
+        );
+        let first = terminal.present();
+        assert!(first.starts_with("\x1b[0m\x1b[2J"));
+
+        assert_eq!(terminal.present(), "");
+
+        terminal.text(
+            Rect::new((0.0, 0.0), (4.0, 1.0)),
+            &(),
+            "b",
+            Color::WHITE,
+            (Right, Top),
+        );
+        assert_eq!(
+            terminal.present(),
+            "\x1b[1;4H\x1b[38;2;255;255;255;49mb\x1b[0m"
+        );
+    }
+}
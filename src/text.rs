@@ -0,0 +1,717 @@
+//! Multi-line text layout.
+
+#![warn(missing_copy_implementations)]
+
+use std::iter::once;
+use std::ops::Range;
+
+use crate::common::*;
+use crate::layout::*;
+use crate::renderer::*;
+
+/// The string appended to lines that were cut short.
+const ELLIPSIS: &str = "…";
+
+/// What happens to text that doesn't fit inside of the group it's laid out in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// All lines are laid out, even if they extend past the group's bottom edge.
+    Visible,
+    /// Lines that don't fit inside of the group's height are left out. At least one line is always laid out.
+    Clip,
+    /// Like [`TextOverflow::Clip`], but if any lines were left out, the last line is ended with an ellipsis. Lines
+    /// that are wider than the group are also cut short with an ellipsis.
+    Ellipsis,
+}
+
+/// Options for laying out multi-line text using [`Ui::paragraph`][crate::Ui::paragraph].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextOptions {
+    /// The alignment of each line (horizontal), and of the whole block of lines (vertical).
+    pub alignment: Alignment,
+    /// Whether lines longer than the group's width are broken into multiple lines.
+    pub wrap: bool,
+    /// The maximum number of lines to lay out. Lines past that are left out, as if they didn't fit into the group.
+    pub max_lines: Option<usize>,
+    /// What happens to lines that don't fit into the group.
+    pub overflow: TextOverflow,
+}
+
+impl TextOptions {
+    /// Creates options for wrapped text with the given alignment, and visible overflow.
+    pub fn new(alignment: Alignment) -> Self {
+        Self {
+            alignment,
+            wrap: true,
+            max_lines: None,
+            overflow: TextOverflow::Visible,
+        }
+    }
+
+    /// Sets whether lines longer than the group's width are wrapped.
+    pub fn wrap(self, wrap: bool) -> Self {
+        Self { wrap, ..self }
+    }
+
+    /// Sets the maximum number of lines.
+    pub fn max_lines(self, max_lines: usize) -> Self {
+        Self {
+            max_lines: Some(max_lines),
+            ..self
+        }
+    }
+
+    /// Sets what happens to lines that don't fit into the group.
+    pub fn overflow(self, overflow: TextOverflow) -> Self {
+        Self { overflow, ..self }
+    }
+}
+
+impl Default for TextOptions {
+    /// The default options lay out wrapped text aligned to the top left corner, with visible overflow.
+    fn default() -> Self {
+        Self::new((Left, Top))
+    }
+}
+
+/// A single line of laid out text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// The text of the line, with trailing whitespace removed. If the line was cut short, this includes the ellipsis.
+    pub text: String,
+    /// The rectangle occupied by the line, one line height tall.
+    pub rect: Rect,
+}
+
+/// The result of laying out multi-line text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextLayout {
+    /// The laid out lines, from top to bottom.
+    pub lines: Vec<TextLine>,
+    /// The width of the widest line and the total height of all lines.
+    pub size: Vector,
+    /// Whether any lines were left out because of the group's height or the maximum number of lines.
+    pub truncated: bool,
+}
+
+/// A span of rich text, laid out using [`Ui::rich_text`][crate::Ui::rich_text]. `F` is the renderer's font type.
+#[derive(Debug)]
+pub struct TextSpan<'a, F> {
+    pub text: &'a str,
+    pub font: &'a F,
+    pub color: Color,
+    /// Whether a line is drawn below the span's text.
+    pub underline: bool,
+    /// Whether a line is drawn through the span's text.
+    pub strikethrough: bool,
+}
+
+impl<'a, F> TextSpan<'a, F> {
+    /// Creates a new span of text with the given font and color, without any decorations.
+    pub fn new(text: &'a str, font: &'a F, color: impl Into<Color>) -> Self {
+        Self {
+            text,
+            font,
+            color: color.into(),
+            underline: false,
+            strikethrough: false,
+        }
+    }
+
+    /// Draws a line below the span's text.
+    pub fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// Draws a line through the span's text.
+    pub fn strikethrough(self) -> Self {
+        Self {
+            strikethrough: true,
+            ..self
+        }
+    }
+}
+
+impl<'a, F> Clone for TextSpan<'a, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, F> Copy for TextSpan<'a, F> {}
+
+/// A part of a span of rich text that's laid out on a single line. Spans that are broken across multiple lines are
+/// made up of multiple pieces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPiece {
+    /// The index of the span this piece belongs to.
+    pub span: usize,
+    /// The text of the piece. If the line was cut short, the last piece on the line includes the ellipsis.
+    pub text: String,
+    /// The rectangle occupied by the piece, spanning the whole height of its line.
+    pub rect: Rect,
+    /// The vertical position of the line's baseline.
+    pub baseline: f32,
+}
+
+/// The result of laying out rich text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichTextLayout {
+    /// The laid out pieces of the spans, in order.
+    pub pieces: Vec<TextPiece>,
+    /// The width of the widest line and the total height of all lines.
+    pub size: Vector,
+    /// Whether any lines were left out because of the group's height or the maximum number of lines.
+    pub truncated: bool,
+}
+
+impl RichTextLayout {
+    /// Returns the rectangles occupied by the span with the given index. A span that's broken across multiple lines
+    /// occupies multiple rectangles, and a span that was left out occupies none.
+    pub fn span_rects(&self, span: usize) -> impl Iterator<Item = Rect> + '_ {
+        self.pieces
+            .iter()
+            .filter(move |piece| piece.span == span)
+            .map(|piece| piece.rect)
+    }
+
+    /// Returns the index of the span under the given point, if any. This can be used for hit-testing links.
+    pub fn span_at(&self, point: Point) -> Option<usize> {
+        self.pieces
+            .iter()
+            .find(|piece| piece.rect.contains(point))
+            .map(|piece| piece.span)
+    }
+}
+
+/// Returns the byte index of the character before the given byte index.
+fn previous_char(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Breaks a single paragraph of text (without newlines) into lines no wider than the given width. Lines are broken
+/// after runs of whitespace; words that don't fit onto a line on their own are broken between characters.
+///
+/// Lines are pushed as byte ranges into the text, with trailing whitespace removed.
+fn wrap_paragraph(
+    text: &str,
+    paragraph: Range<usize>,
+    max_width: f32,
+    measure: &impl Fn(Range<usize>) -> f32,
+    lines: &mut Vec<Range<usize>>,
+) {
+    if paragraph.is_empty() {
+        lines.push(paragraph);
+        return;
+    }
+    let mut start = paragraph.start;
+    while start < paragraph.end {
+        let rest = &text[start..paragraph.end];
+        let line = |end: usize| start..start + rest[..end].trim_end().len();
+        let breaks = rest
+            .char_indices()
+            .zip(rest.chars().skip(1))
+            .filter(|((_, c), next)| c.is_whitespace() && !next.is_whitespace())
+            .map(|((i, c), _)| i + c.len_utf8())
+            .chain(once(rest.len()));
+        let mut end = None;
+        for candidate in breaks {
+            let range = line(candidate);
+            if text[range.clone()].trim_start().is_empty() {
+                continue;
+            }
+            if measure(range) > max_width {
+                break;
+            }
+            end = Some(candidate);
+        }
+        let end = end.unwrap_or_else(|| {
+            // the first word doesn't fit onto the line on its own, so it's broken at the last character that fits,
+            // keeping at least one character so that the layout always progresses
+            let mut chars = rest.char_indices().map(|(i, c)| i + c.len_utf8());
+            let first = chars.next().unwrap_or(rest.len());
+            chars
+                .take_while(|&end| measure(start..start + end) <= max_width)
+                .last()
+                .unwrap_or(first)
+        });
+        lines.push(line(end));
+        start += end;
+    }
+}
+
+/// Breaks text into lines at newlines, and if wrapping is enabled, wherever the lines get wider than the given width.
+/// `measure` returns the width of the given byte range of the text.
+fn break_lines(
+    text: &str,
+    wrap: bool,
+    max_width: f32,
+    measure: &impl Fn(Range<usize>) -> f32,
+) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for paragraph in text.split('\n') {
+        let end = start + paragraph.strip_suffix('\r').unwrap_or(paragraph).len();
+        if wrap {
+            wrap_paragraph(text, start..end, max_width, measure, &mut lines);
+        } else {
+            lines.push(start..end);
+        }
+        start += paragraph.len() + 1;
+    }
+    lines
+}
+
+/// Returns the number of lines that should be laid out, given the heights of all the lines.
+fn visible_lines(
+    options: TextOptions,
+    available_height: f32,
+    heights: impl Iterator<Item = f32>,
+) -> usize {
+    let max_lines = options.max_lines.unwrap_or(usize::MAX);
+    if options.overflow == TextOverflow::Visible {
+        return max_lines;
+    }
+    let mut height = 0.0;
+    let fitting = heights
+        .take_while(|line_height| {
+            height += line_height;
+            height <= available_height
+        })
+        .count();
+    max_lines.min(fitting.max(1))
+}
+
+/// Cuts the line short until `fits` returns true for it, or the line is empty. `fits` should check whether the line
+/// fits along with an ellipsis. Returns the shortened line, with trailing whitespace removed.
+fn ellipsize(text: &str, line: Range<usize>, fits: impl Fn(Range<usize>) -> bool) -> Range<usize> {
+    let mut end = line.end;
+    loop {
+        let range = line.start..line.start + text[line.start..end].trim_end().len();
+        if end == line.start || fits(range.clone()) {
+            return range;
+        }
+        end = previous_char(text, end);
+    }
+}
+
+/// Returns the vertical position of the top of a block of text with the given height.
+fn block_top(rect: Rect, height: f32, alignment: AlignV) -> f32 {
+    match alignment {
+        Top => rect.top(),
+        Middle => rect.center_y() - height / 2.0,
+        Bottom => rect.bottom() - height,
+    }
+}
+
+/// Returns the horizontal position of the left edge of a line with the given width.
+fn line_left(rect: Rect, width: f32, alignment: AlignH) -> f32 {
+    match alignment {
+        Left => rect.left(),
+        Center => rect.center_x() - width / 2.0,
+        Right => rect.right() - width,
+    }
+}
+
+/// Lays out text inside of the given rectangle, as described by [`TextOptions`]. `measure` returns the width of a
+/// single line of text, and every line is `line_height` tall.
+pub(crate) fn layout_text(
+    rect: Rect,
+    text: &str,
+    options: TextOptions,
+    line_height: f32,
+    measure: impl Fn(&str) -> f32,
+) -> TextLayout {
+    let measure_range = |range: Range<usize>| measure(&text[range]);
+    let mut lines = break_lines(text, options.wrap, rect.width(), &measure_range);
+    let visible = visible_lines(options, rect.height(), lines.iter().map(|_| line_height));
+    let truncated = lines.len() > visible;
+    lines.truncate(visible);
+
+    let last = lines.len().saturating_sub(1);
+    let lines: Vec<String> = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let overflows = (truncated && i == last) || measure_range(line.clone()) > rect.width();
+            if options.overflow == TextOverflow::Ellipsis && overflows {
+                let line = ellipsize(text, line, |line| {
+                    measure(&format!("{}{}", &text[line], ELLIPSIS)) <= rect.width()
+                });
+                format!("{}{}", &text[line], ELLIPSIS)
+            } else {
+                text[line].to_owned()
+            }
+        })
+        .collect();
+
+    let height = lines.len() as f32 * line_height;
+    let top = block_top(rect, height, options.alignment.1);
+    let mut width = 0.0f32;
+    let lines = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let line_width = measure(&text);
+            width = width.max(line_width);
+            let x = line_left(rect, line_width, options.alignment.0);
+            TextLine {
+                text,
+                rect: Rect::new((x, top + i as f32 * line_height), (line_width, line_height)),
+            }
+        })
+        .collect();
+    TextLayout {
+        lines,
+        size: vector(width, height),
+        truncated,
+    }
+}
+
+/// Lays out spans of rich text inside of the given rectangle, as described by [`TextOptions`]. `measure` returns the
+/// metrics of a single line of text set in the given font.
+///
+/// Each line is as tall as the tallest font used on it, and the spans on a line share a common baseline.
+pub(crate) fn layout_rich_text<F>(
+    rect: Rect,
+    spans: &[TextSpan<'_, F>],
+    options: TextOptions,
+    measure: impl Fn(&F, &str) -> TextMetrics,
+) -> RichTextLayout {
+    if spans.is_empty() {
+        return RichTextLayout::default();
+    }
+    let text: String = spans.iter().map(|span| span.text).collect();
+    let starts: Vec<usize> = spans
+        .iter()
+        .scan(0, |start, span| {
+            let span_start = *start;
+            *start += span.text.len();
+            Some(span_start)
+        })
+        .collect();
+    let (text, starts) = (text.as_str(), starts.as_slice());
+    // the span that the byte at the given index belongs to
+    let span_at = |index: usize| {
+        starts
+            .partition_point(|&start| start <= index)
+            .saturating_sub(1)
+    };
+    // the span that the last character of the range belongs to
+    let last_span = |range: &Range<usize>| span_at(range.end.saturating_sub(1).max(range.start));
+    // the non-empty parts of the spans that make up the given range
+    let parts = |range: Range<usize>| {
+        (0..spans.len()).filter_map(move |i| {
+            let start = range.start.max(starts[i]);
+            let end = range.end.min(starts[i] + spans[i].text.len());
+            if start < end {
+                Some((i, start..end))
+            } else {
+                None
+            }
+        })
+    };
+    let measure_range = |range: Range<usize>| -> f32 {
+        parts(range)
+            .map(|(i, part)| measure(spans[i].font, &text[part]).width)
+            .sum()
+    };
+    let ellipsis_width = |span: usize| measure(spans[span].font, ELLIPSIS).width;
+
+    let mut lines = break_lines(text, options.wrap, rect.width(), &measure_range);
+    let metrics: Vec<TextMetrics> = lines
+        .iter()
+        .map(|line| {
+            let mut fonts: Vec<usize> = parts(line.clone()).map(|(i, _)| i).collect();
+            if fonts.is_empty() {
+                // empty lines take the metrics of the span they're in
+                fonts.push(span_at(line.start));
+            }
+            fonts.into_iter().map(|i| measure(spans[i].font, "")).fold(
+                TextMetrics::default(),
+                |a, b| TextMetrics {
+                    width: 0.0,
+                    ascent: a.ascent.max(b.ascent),
+                    descent: a.descent.max(b.descent),
+                    line_height: a.line_height.max(b.line_height),
+                },
+            )
+        })
+        .collect();
+    let visible = visible_lines(
+        options,
+        rect.height(),
+        metrics.iter().map(|metrics| metrics.line_height),
+    );
+    let truncated = lines.len() > visible;
+    lines.truncate(visible);
+
+    let height: f32 = metrics[..lines.len()]
+        .iter()
+        .map(|metrics| metrics.line_height)
+        .sum();
+    let mut y = block_top(rect, height, options.alignment.1);
+    let mut width = 0.0f32;
+    let mut pieces = Vec::new();
+    let last = lines.len().saturating_sub(1);
+    for (i, (line, metrics)) in lines.into_iter().zip(metrics).enumerate() {
+        let overflows = (truncated && i == last) || measure_range(line.clone()) > rect.width();
+        let (line, ellipsis) = if options.overflow == TextOverflow::Ellipsis && overflows {
+            let line = ellipsize(text, line, |line| {
+                measure_range(line.clone()) + ellipsis_width(last_span(&line)) <= rect.width()
+            });
+            let span = last_span(&line);
+            (line, Some(span))
+        } else {
+            (line, None)
+        };
+
+        let mut line_pieces: Vec<(usize, String)> = parts(line)
+            .map(|(span, part)| (span, text[part].to_owned()))
+            .collect();
+        if let Some(span) = ellipsis {
+            match line_pieces.last_mut() {
+                Some((last, text)) if *last == span => text.push_str(ELLIPSIS),
+                _ => line_pieces.push((span, ELLIPSIS.to_owned())),
+            }
+        }
+
+        let widths: Vec<f32> = line_pieces
+            .iter()
+            .map(|(span, text)| measure(spans[*span].font, text).width)
+            .collect();
+        let line_width: f32 = widths.iter().sum();
+        width = width.max(line_width);
+        let mut x = line_left(rect, line_width, options.alignment.0);
+        let leading = metrics.line_height - (metrics.ascent + metrics.descent);
+        let baseline = y + leading / 2.0 + metrics.ascent;
+        for ((span, text), piece_width) in line_pieces.into_iter().zip(widths) {
+            pieces.push(TextPiece {
+                span,
+                text,
+                rect: Rect::new((x, y), (piece_width, metrics.line_height)),
+                baseline,
+            });
+            x += piece_width;
+        }
+        y += metrics.line_height;
+    }
+    RichTextLayout {
+        pieces,
+        size: vector(width, height),
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DrawCommand, RecordingRenderer, Ui};
+
+    /// Lays out text using a monospace font, where every character is one unit wide and lines are two units tall.
+    fn monospace(rect: Rect, text: &str, options: TextOptions) -> TextLayout {
+        layout_text(rect, text, options, 2.0, |text| text.chars().count() as f32)
+    }
+
+    fn texts(layout: &TextLayout) -> Vec<&str> {
+        layout.lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn wrapping_and_newlines() {
+        let rect = Rect::new((0.0, 0.0), (10.0, 100.0));
+        let layout = monospace(
+            rect,
+            "the quick brown fox\n\njumps over a dog named supercalifragilistic",
+            TextOptions::default(),
+        );
+        assert_eq!(
+            texts(&layout),
+            [
+                "the quick",
+                "brown fox",
+                "",
+                "jumps over",
+                "a dog",
+                "named",
+                "supercalif",
+                "ragilistic"
+            ]
+        );
+        assert_eq!(layout.size, vector(10.0, 16.0));
+        assert!(!layout.truncated);
+
+        let layout = monospace(rect, "no wrapping here", TextOptions::default().wrap(false));
+        assert_eq!(texts(&layout), ["no wrapping here"]);
+    }
+
+    #[test]
+    fn alignment() {
+        let rect = Rect::new((10.0, 10.0), (10.0, 10.0));
+        let layout = monospace(rect, "centered text", TextOptions::new(CENTER));
+        assert_eq!(layout.lines[0].rect, Rect::new((11.0, 13.0), (8.0, 2.0)));
+        assert_eq!(layout.lines[1].rect, Rect::new((13.0, 15.0), (4.0, 2.0)));
+
+        let layout = monospace(rect, "a\nbc", TextOptions::new((Right, Bottom)));
+        assert_eq!(layout.lines[0].rect, Rect::new((19.0, 16.0), (1.0, 2.0)));
+        assert_eq!(layout.lines[1].rect, Rect::new((18.0, 18.0), (2.0, 2.0)));
+    }
+
+    #[test]
+    fn truncation() {
+        let rect = Rect::new((0.0, 0.0), (6.0, 5.0));
+        let text = "one two three four";
+        let layout = monospace(rect, text, TextOptions::default());
+        assert_eq!(texts(&layout), ["one", "two", "three", "four"]);
+
+        let layout = monospace(
+            rect,
+            text,
+            TextOptions::default().overflow(TextOverflow::Clip),
+        );
+        assert_eq!(texts(&layout), ["one", "two"]);
+        assert!(layout.truncated);
+
+        let layout = monospace(
+            rect,
+            text,
+            TextOptions::default().overflow(TextOverflow::Ellipsis),
+        );
+        assert_eq!(texts(&layout), ["one", "two…"]);
+        assert_eq!(layout.size, vector(4.0, 4.0));
+
+        let layout = monospace(rect, text, TextOptions::default().max_lines(1));
+        assert_eq!(texts(&layout), ["one"]);
+
+        let layout = monospace(
+            rect,
+            "long line",
+            TextOptions::default()
+                .wrap(false)
+                .overflow(TextOverflow::Ellipsis),
+        );
+        assert_eq!(texts(&layout), ["long…"]);
+        assert!(!layout.truncated);
+    }
+
+    /// Lays out rich text where the font is a scale: characters are as wide as the scale, and lines are twice as
+    /// tall, with the ascent taking up half of the line and the descent a quarter.
+    fn scaled(rect: Rect, spans: &[TextSpan<'_, f32>], options: TextOptions) -> RichTextLayout {
+        layout_rich_text(rect, spans, options, |&scale, text| TextMetrics {
+            width: text.chars().count() as f32 * scale,
+            ascent: scale,
+            descent: scale / 2.0,
+            line_height: scale * 2.0,
+        })
+    }
+
+    #[test]
+    fn rich_text_shares_baselines() {
+        let (small, big) = (1.0, 2.0);
+        let spans = [
+            TextSpan::new("small ", &small, Color::WHITE),
+            TextSpan::new("BIG", &big, Color::WHITE),
+            TextSpan::new(" wrapped", &small, Color::WHITE),
+        ];
+        let layout = scaled(
+            Rect::new((0.0, 0.0), (12.0, 100.0)),
+            &spans,
+            TextOptions::default(),
+        );
+        let pieces: Vec<_> = layout
+            .pieces
+            .iter()
+            .map(|piece| (piece.span, piece.text.as_str(), piece.rect, piece.baseline))
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                (0, "small ", Rect::new((0.0, 0.0), (6.0, 4.0)), 2.5),
+                (1, "BIG", Rect::new((6.0, 0.0), (6.0, 4.0)), 2.5),
+                (2, "wrapped", Rect::new((0.0, 4.0), (7.0, 2.0)), 5.25),
+            ]
+        );
+        assert_eq!(layout.size, vector(12.0, 6.0));
+        assert_eq!(layout.span_at(point(7.0, 1.0)), Some(1));
+        assert_eq!(layout.span_at(point(10.0, 5.0)), None);
+        assert_eq!(
+            layout.span_rects(2).collect::<Vec<_>>(),
+            [Rect::new((0.0, 4.0), (7.0, 2.0))]
+        );
+    }
+
+    #[test]
+    fn rich_text_spans_break_across_lines() {
+        let scale = 1.0;
+        let spans = [
+            TextSpan::new("see ", &scale, Color::WHITE),
+            TextSpan::new("the manual", &scale, Color::WHITE).underline(),
+            TextSpan::new("'s index", &scale, Color::WHITE),
+        ];
+        let rect = Rect::new((0.0, 0.0), (8.0, 4.0));
+        let layout = scaled(rect, &spans, TextOptions::new((Center, Top)));
+        let texts: Vec<_> = layout
+            .pieces
+            .iter()
+            .map(|piece| (piece.span, piece.text.as_str()))
+            .collect();
+        // words are never broken where spans meet
+        assert_eq!(
+            texts,
+            [
+                (0, "see "),
+                (1, "the"),
+                (1, "manual"),
+                (2, "'s"),
+                (2, "index")
+            ]
+        );
+        assert_eq!(layout.span_rects(1).count(), 2);
+        assert_eq!(layout.pieces[1].rect, Rect::new((4.5, 0.0), (3.0, 2.0)));
+
+        let layout = scaled(
+            rect,
+            &spans,
+            TextOptions::default().overflow(TextOverflow::Ellipsis),
+        );
+        assert!(layout.truncated);
+        let texts: Vec<_> = layout
+            .pieces
+            .iter()
+            .map(|piece| (piece.span, piece.text.as_str()))
+            .collect();
+        assert_eq!(texts, [(0, "see "), (1, "the"), (1, "manual"), (2, "'…")]);
+    }
+
+    #[test]
+    fn rich_text_draws_decorations() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((100.0, 100.0), Layout::Freeform);
+        let font = NoRendererFont;
+        let layout = ui.rich_text(
+            &[
+                TextSpan::new("plain ", &font, Color::WHITE),
+                TextSpan::new("link", &font, Color::BLACK).underline(),
+            ],
+            TextOptions::default(),
+        );
+        assert_eq!(layout.pieces.len(), 2);
+        let commands: Vec<_> = ui
+            .renderer()
+            .commands()
+            .iter()
+            .map(|command| match command {
+                DrawCommand::Text { text, .. } => text.as_str(),
+                DrawCommand::Line { .. } => "line",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(commands, ["plain ", "link", "line"]);
+    }
+}
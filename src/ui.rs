@@ -1,10 +1,25 @@
 //! The core and state for laying out groups.
 
+use std::any::Any;
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+use std::hash::Hash;
 use std::ops::{Deref, DerefMut};
+#[cfg(debug_assertions)]
+use std::panic::Location;
 
 use crate::common::*;
+use crate::error::*;
+use crate::focus::*;
+use crate::id::*;
+use crate::image::*;
+use crate::input::*;
+use crate::layer::*;
 use crate::layout::*;
+use crate::recording::*;
 use crate::renderer::*;
+use crate::state::*;
+use crate::text::*;
 
 #[derive(Clone)]
 struct Group {
@@ -14,10 +29,72 @@ struct Group {
     rect: Rect,
     layout: Layout,
     cursor: Vector,
+    /// The clip region set using `clip()`, in absolute coordinates. Used for hit-testing.
+    clip: Rect,
+    /// Sizes of children resolved by `flex()`, consumed in order by `push_flex()`.
+    flex: Vec<f32>,
+    next_flex: usize,
+    /// The extent of the current line along the cross axis, in wrapping layouts.
+    line_extent: f32,
+    /// The bounding box of all popped subgroups, in absolute coordinates.
+    content: Option<Rect>,
+    /// Gaps between subgroups (main) and lines (cross), in wrapping layouts.
+    main_gap: f32,
+    cross_gap: f32,
+    /// The grid state, in grid layouts.
+    grid: Option<Grid>,
+    /// Whether this group holds the content of a scroll area.
+    scroll: bool,
+    /// The length of the ID stack when this group was pushed.
+    id_depth: usize,
+    /// Where the group was pushed, for diagnosing unbalanced pushes.
+    #[cfg(debug_assertions)]
+    location: &'static Location<'static>,
     //
     // rendering info
     //
     line_cap: LineCap,
+    layer: Layer,
+}
+
+impl Group {
+    #[track_caller]
+    fn new(
+        rect: Rect,
+        layout: Layout,
+        clip: Rect,
+        id_depth: usize,
+        line_cap: LineCap,
+        layer: Layer,
+    ) -> Self {
+        Self {
+            rect,
+            layout,
+            cursor: vector(0.0, 0.0),
+            clip,
+            flex: Vec::new(),
+            next_flex: 0,
+            line_extent: 0.0,
+            content: None,
+            main_gap: 0.0,
+            cross_gap: 0.0,
+            grid: None,
+            scroll: false,
+            id_depth,
+            #[cfg(debug_assertions)]
+            location: Location::caller(),
+            line_cap,
+            layer,
+        }
+    }
+
+    /// Resolves the grid's tracks again, to fill the group's current size with the current gaps.
+    fn resize_grid(&mut self) {
+        let gap = vector(self.main_gap, self.cross_gap);
+        if let Some(grid) = &mut self.grid {
+            grid.resize(self.rect.size, gap);
+        }
+    }
 }
 
 // for use in doc comment
@@ -73,8 +150,8 @@ use crate::build;
 /// use paws::Layout;
 ///
 /// impl Slider {
-///     // for brevity, we'll assume no input events need to be processed, as that's outside
-///     // of paws's scope.
+///     // for brevity, we'll assume no input events need to be processed. see the Input section
+///     // below for how that's done.
 ///     fn process(&mut self, ui: &mut Ui, width: f32) {
 ///         // create a group that'll span a rectangle with the provided width and the parent
 ///         // group's height
@@ -136,7 +213,8 @@ use crate::build;
 /// of [`Ui::new`] (obviously), and [`Ui::root`]. The latter is used to initialize the UI state so that at least one
 /// group is present, but also to make sure that the group stack doesn't grow to oblivion if the user forgets to pop a
 /// group or two. (This isn't a good excuse for not popping groups, but it'll at least prevent your program from leaking
-/// memory if you really do forget.)
+/// memory if you really do forget.) To catch such mistakes, call [`Ui::finish`] at the end of each frame, which reports
+/// any groups that weren't popped.
 ///
 /// # Panicking
 ///
@@ -152,13 +230,79 @@ use crate::build;
 /// The renderer can be retrieved as an immutable reference (for probing and measurements) using [`Ui::renderer`],
 /// and as a mutable reference (for doing actual rendering) using [`Ui::render`].
 ///
+/// # Input
+///
+/// paws doesn't process input events by itself, but it can hit-test groups against a snapshot of the input state.
+/// Feed your windowing library's events into an [`Input`], and pass it to the UI using [`Ui::set_input`] at the
+/// start of every frame. Then, methods like [`Ui::hovered`] and [`Ui::clicked`] can be used to check whether the
+/// pointer interacts with the current group.
+///
+/// Widgets that need to keep receiving input after the pointer leaves them, such as sliders being dragged, should use
+/// [`Ui::interact`] instead. It tracks a single _hot_ (hovered) and _active_ (pressed and held) widget across frames,
+/// and the active widget captures the pointer until the button is released.
+///
+/// # Keyboard focus
+///
+/// Widgets that can be operated using the keyboard register themselves using [`Ui::focusable`], in the order they're
+/// pushed. Tab and Shift+Tab (fed in through [`Input::press_key`]) move focus to the next and previous registered
+/// widget, and focus can also be moved programmatically using [`Ui::set_focus`].
+///
+/// # Layers
+///
+/// Because paws draws while laying things out, popups like dropdowns and tooltips, which usually live inside of deeply
+/// nested groups, would end up underneath their parents' later siblings. To prevent that, groups can be moved onto a
+/// different [`Layer`] using [`Ui::set_layer`]. Drawing onto layers other than [`Layer::Normal`] is buffered, and
+/// the buffers are drawn in layer order upon calling [`Ui::flush_layers`] at the end of the frame.
+///
+/// Groups on higher layers also block input from reaching groups on lower layers beneath them, so an open dropdown
+/// doesn't let clicks through to the widgets it covers.
+///
+/// # Widget IDs
+///
+/// Widgets that need to be told apart across frames (eg. for tracking which one is being dragged) can be given an
+/// [`Id`] using [`Ui::id`]. IDs are hashed from a key and the enclosing ID scope, and scopes can be pushed with
+/// [`Ui::push_id`] to disambiguate widgets created in loops. ID scopes are popped along with the group they were
+/// pushed in, and in debug builds, creating the same ID twice in a single frame panics.
+///
+/// IDs can also be used to store small bits of widget state inside of the `Ui` itself, using [`Ui::state`]. This is
+/// handy for state that would be unwieldy to keep in your own structs, such as whether each row of a dynamic list
+/// is expanded.
+///
 /// # `build!`
 ///
 /// For your convenience while building UIs, a macro is available to make all those `push`es and `pop`s get out of your
 /// face. See [`build!`]'s documentation for more info.
+///
+/// If your UI code uses early returns (eg. with `?`), [`Ui::group`] and [`Ui::scoped`] can be used instead. They pop
+/// the group automatically, no matter how the code inside of it exits.
 pub struct Ui<T: Renderer> {
     stack: Vec<Group>,
     renderer: T,
+    input: Input,
+    ids: Vec<Id>,
+    #[cfg(debug_assertions)]
+    seen_ids: HashSet<Id>,
+    state: StateMap,
+    /// The hot widget, as determined during the previous frame.
+    hot: Option<Id>,
+    /// The hot widget candidate for the next frame, its layer, and the button pressed on it if it wasn't hot yet.
+    /// Widgets registered later are on top, so the last one on the highest layer wins.
+    next_hot: Option<(Id, Layer, Option<MouseButton>)>,
+    /// The active widget, and the button that activated it.
+    active: Option<(Id, MouseButton)>,
+    /// Whether the active widget was activated by `root`, and its press wasn't reported yet.
+    press_pending: bool,
+    /// Whether the active widget was registered during the current frame.
+    active_seen: bool,
+    focus: Focus,
+    /// Buffered draw commands of the deferred layers.
+    layers: [LayerBuffer<T>; 2],
+    /// Used for buffering draw calls on the deferred layers. Set upon moving a group onto a layer.
+    cloner: Option<Cloner<T>>,
+    /// Rectangles of groups on deferred layers that were drawn during the previous frame, which block input from
+    /// reaching groups on lower layers.
+    blockers: Vec<(Layer, Rect)>,
+    next_blockers: Vec<(Layer, Rect)>,
 }
 
 impl<T: Renderer> Ui<T> {
@@ -167,6 +311,21 @@ impl<T: Renderer> Ui<T> {
         Self {
             stack: Vec::new(),
             renderer,
+            input: Input::new(),
+            ids: Vec::new(),
+            #[cfg(debug_assertions)]
+            seen_ids: HashSet::new(),
+            state: StateMap::default(),
+            hot: None,
+            next_hot: None,
+            active: None,
+            press_pending: false,
+            active_seen: false,
+            focus: Focus::default(),
+            layers: [Vec::new(), Vec::new()],
+            cloner: None,
+            blockers: Vec::new(),
+            next_blockers: Vec::new(),
         }
     }
 
@@ -219,6 +378,11 @@ impl<T: Renderer> Ui<T> {
         self.top().rect
     }
 
+    /// Returns the current group's rectangle, or an error if there are no groups on the stack.
+    pub fn try_rect(&self) -> Result<Rect, UiError> {
+        Ok(self.try_top()?.rect)
+    }
+
     /// Returns the root group's rectangle.
     pub fn root_rect(&self) -> Rect {
         self.stack.first().expect("no root group").rect
@@ -226,12 +390,16 @@ impl<T: Renderer> Ui<T> {
 
     /// Returns the "remaining size" of the current group. This is measured by subtracting the group's cursor from
     /// its size, effectively giving you the size that remains in the group. In reversed layouts, the cursor is added
-    /// instead, as it goes into the negative. On the freeform layout, this always returns (0, 0).
+    /// instead, as it goes into the negative. In wrapping layouts, this is the size remaining in the current line.
+    /// On the freeform and grid layouts, this always returns (0, 0).
     pub fn remaining_size(&self) -> Vector {
         let top = self.top();
         match top.layout {
-            Layout::Freeform => vector(0.0, 0.0),
-            Layout::Horizontal | Layout::Vertical => top.rect.size - top.cursor,
+            Layout::Freeform | Layout::Grid => vector(0.0, 0.0),
+            Layout::Horizontal
+            | Layout::Vertical
+            | Layout::HorizontalWrap
+            | Layout::VerticalWrap => top.rect.size - top.cursor,
             Layout::HorizontalRev | Layout::VerticalRev => top.rect.size + top.cursor,
         }
     }
@@ -241,11 +409,12 @@ impl<T: Renderer> Ui<T> {
     pub fn remaining_width(&self) -> f32 {
         let top = self.top();
         match top.layout {
-            Layout::Freeform => 0.0,
+            Layout::Freeform | Layout::Grid => 0.0,
             Layout::Horizontal => top.rect.width() - top.cursor.x,
             Layout::Vertical => top.rect.width(),
             Layout::HorizontalRev => top.rect.width() + top.cursor.x,
             Layout::VerticalRev => top.rect.width(),
+            Layout::HorizontalWrap | Layout::VerticalWrap => top.rect.width() - top.cursor.x,
         }
     }
 
@@ -254,11 +423,12 @@ impl<T: Renderer> Ui<T> {
     pub fn remaining_height(&self) -> f32 {
         let top = self.top();
         match top.layout {
-            Layout::Freeform => 0.0,
+            Layout::Freeform | Layout::Grid => 0.0,
             Layout::Horizontal => top.rect.height(),
             Layout::Vertical => top.rect.height() - top.cursor.y,
             Layout::HorizontalRev => top.rect.height(),
             Layout::VerticalRev => top.rect.height() + top.cursor.y,
+            Layout::HorizontalWrap | Layout::VerticalWrap => top.rect.height() - top.cursor.y,
         }
     }
 
@@ -272,48 +442,289 @@ impl<T: Renderer> Ui<T> {
     ///
     /// Note that this root group **must not** be popped off manually, as it gets popped off every frame anyways,
     /// because the stack is cleared upon calling this function.
+    ///
+    /// This also starts a new frame for widget state: any state stored with [`Ui::state`] that wasn't accessed since
+    /// the previous call to `root` is dropped, and the hot widget is updated (see [`Ui::interact`]). If the active
+    /// widget wasn't registered during the previous frame, it's deactivated. The same goes for the focused widget
+    /// (see [`Ui::focusable`]).
+    ///
+    /// Anything left in the layer buffers since the previous frame is discarded, so don't forget to call
+    /// [`Ui::flush_layers`] at the end of each frame.
     pub fn root(&mut self, size: impl Into<Vector>, layout: Layout) {
         self.stack.clear();
-        self.stack.push(Group {
-            rect: Rect::new(point(0.0, 0.0), size),
+        self.state.collect_garbage();
+        self.focus.next_frame();
+        for buffer in &mut self.layers {
+            buffer.clear();
+        }
+        self.blockers = std::mem::take(&mut self.next_blockers);
+        let next_hot = self.next_hot.take();
+        self.hot = next_hot.map(|(id, _, _)| id);
+        if !self.active_seen {
+            self.active = None;
+        }
+        self.active_seen = false;
+        self.press_pending = false;
+        if let (None, Some((id, _, Some(button)))) = (self.active, next_hot) {
+            self.active = Some((id, button));
+            self.press_pending = true;
+        }
+        self.ids.clear();
+        self.ids.push(Id::ROOT);
+        #[cfg(debug_assertions)]
+        self.seen_ids.clear();
+        let rect = Rect::new(point(0.0, 0.0), size);
+        self.stack.push(Group::new(
+            rect,
             layout,
-            cursor: vector(0.0, 0.0),
-            line_cap: LineCap::Butt,
-        });
+            rect,
+            1,
+            LineCap::Butt,
+            Layer::Normal,
+        ));
     }
 
     /// Pushes a group onto the group stack, with the given size and layout.
+    ///
+    /// In debug builds, the source location of the call is recorded, so that [`Ui::finish`] can point to pushes that
+    /// weren't popped.
+    #[track_caller]
     pub fn push(&mut self, size: impl Into<Vector>, layout: Layout) {
+        expect_ok(self.try_push(size, layout));
+    }
+
+    /// Pushes a group onto the group stack, with the given size and layout.
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::MissingGrid`] if the current group uses the grid layout, but its tracks weren't declared.
+    #[track_caller]
+    pub fn try_push(&mut self, size: impl Into<Vector>, layout: Layout) -> Result<(), UiError> {
         let size = size.into();
-        let top = self.top().clone();
-        let position = match top.layout {
-            Layout::Freeform | Layout::Horizontal | Layout::Vertical => {
-                top.rect.position + top.cursor
+        let top = self.try_top_mut()?;
+        if top.layout == Layout::Grid && top.grid.is_none() {
+            return Err(UiError::MissingGrid);
+        }
+        // in wrapping layouts, move onto a new line if the group would overflow the current one. a line always holds
+        // at least one group, even if it overflows
+        match top.layout {
+            Layout::HorizontalWrap
+                if top.cursor.x > 0.0 && top.cursor.x + size.x > top.rect.width() =>
+            {
+                top.cursor = vector(0.0, top.cursor.y + top.line_extent + top.cross_gap);
+                top.line_extent = 0.0;
+            }
+            Layout::VerticalWrap
+                if top.cursor.y > 0.0 && top.cursor.y + size.y > top.rect.height() =>
+            {
+                top.cursor = vector(top.cursor.x + top.line_extent + top.cross_gap, 0.0);
+                top.line_extent = 0.0;
             }
+            _ => (),
+        }
+        let position = match top.layout {
+            Layout::Freeform
+            | Layout::Horizontal
+            | Layout::Vertical
+            | Layout::HorizontalWrap
+            | Layout::VerticalWrap => top.rect.position + top.cursor,
             Layout::HorizontalRev => top.rect.top_right() + top.cursor - point(size.x, 0.0),
             Layout::VerticalRev => top.rect.bottom_left() + top.cursor - point(0.0, size.y),
+            Layout::Grid => {
+                let cell = top.grid.as_mut().ok_or(UiError::MissingGrid)?.place();
+                top.rect.position + cell.position
+            }
         };
-        self.stack.push(Group {
-            rect: Rect::new(position, size),
+        let (clip, line_cap, layer) = (top.clip, top.line_cap, top.layer);
+        let id_depth = self.ids.len();
+        self.stack.push(Group::new(
+            Rect::new(position, size),
             layout,
-            cursor: point(0.0, 0.0),
-            ..top
-        });
+            clip,
+            id_depth,
+            line_cap,
+            layer,
+        ));
+        Ok(())
     }
 
-    /// Pops a group off the group stack, updating the cursor of the group under it.
+    /// Pops a group off the group stack, updating the cursor of the group under it. Any ID scopes pushed inside of the
+    /// group are popped along with it.
+    ///
+    /// # Panics
+    ///  - If the current group is the root group, which means there are more `pop()`s than `push()`es.
+    ///  - If the current group is the content of a scroll area, which must be popped with [`Ui::pop_scroll`].
+    #[track_caller]
     pub fn pop(&mut self) {
-        let group = self
-            .stack
-            .pop()
-            .expect("the root group got popped of the stack");
+        match self.try_pop() {
+            Ok(()) => (),
+            Err(UiError::MissingParent) => panic!(
+                "pop() without a matching push(): the root group can't be popped. check your push() and pop()s"
+            ),
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Pops a group off the group stack, updating the cursor of the group under it.
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::MissingParent`] if the current group is the root group.
+    ///  - [`UiError::ScrollAreaNotPopped`] if the current group is the content of a scroll area.
+    pub fn try_pop(&mut self) -> Result<(), UiError> {
+        if self.try_top()?.scroll {
+            return Err(UiError::ScrollAreaNotPopped);
+        }
+        self.pop_group()
+    }
+
+    /// Pops a group off the group stack like [`Ui::try_pop`], but also allows popping the contents of scroll areas.
+    fn pop_group(&mut self) -> Result<(), UiError> {
+        match self.stack.len() {
+            0 => return Err(UiError::EmptyStack),
+            1 => return Err(UiError::MissingParent),
+            _ => (),
+        }
+        let group = self.stack.pop().unwrap();
+        self.ids.truncate(group.id_depth);
         let top = self.top_mut();
+        if group.layer > top.layer {
+            let blocker = group.clip.intersection(group.rect);
+            self.next_blockers.push((group.layer, blocker));
+        }
+        let top = self.top_mut();
+        top.content = Some(match top.content {
+            Some(content) => content.union(group.rect),
+            None => group.rect,
+        });
         match top.layout {
             Layout::Freeform => (),
+            Layout::Grid => {
+                if let Some(grid) = &mut top.grid {
+                    grid.fit_current(group.rect.size);
+                }
+            }
             Layout::Horizontal => top.cursor.x += group.rect.width(),
             Layout::Vertical => top.cursor.y += group.rect.height(),
             Layout::HorizontalRev => top.cursor.x -= group.rect.width(),
             Layout::VerticalRev => top.cursor.y -= group.rect.height(),
+            Layout::HorizontalWrap => {
+                top.cursor.x += group.rect.width() + top.main_gap;
+                top.line_extent = top.line_extent.max(group.rect.height());
+            }
+            Layout::VerticalWrap => {
+                top.cursor.y += group.rect.height() + top.main_gap;
+                top.line_extent = top.line_extent.max(group.rect.width());
+            }
+        }
+        Ok(())
+    }
+
+    /// Finishes the frame: flushes the layers (see [`Ui::flush_layers`]) and verifies that all groups pushed during
+    /// the frame were popped, leaving only the root group on the stack.
+    ///
+    /// Unbalanced pushes would otherwise go unnoticed, as [`Ui::root`] clears the stack at the start of every frame.
+    /// In debug builds, the returned error names the source location of every push that wasn't popped.
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 600.0), Layout::Vertical);
+    /// ui.push((100.0, 100.0), Layout::Freeform);
+    /// let error = ui.finish().unwrap_err();
+    /// assert_eq!(error.count(), 1);
+    /// ```
+    pub fn finish(&mut self) -> Result<(), UnbalancedGroups> {
+        self.flush_layers();
+        if self.stack.len() <= 1 {
+            return Ok(());
+        }
+        Err(UnbalancedGroups {
+            locations: self.stack[1..]
+                .iter()
+                .map(|_group| {
+                    #[cfg(debug_assertions)]
+                    {
+                        Some(_group.location)
+                    }
+                    #[cfg(not(debug_assertions))]
+                    {
+                        None
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    /// Pushes a group onto the group stack, and returns a guard that pops it when dropped. The guard can be used just
+    /// like the `Ui` itself.
+    ///
+    /// When the guard is dropped, all groups pushed on top of its group that weren't popped yet are popped, too, so
+    /// early returns can't leave the stack unbalanced.
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, Ui};
+    ///
+    /// fn widget(ui: &mut Ui<NoRenderer>, fail: bool) -> Result<(), &'static str> {
+    ///     let mut ui = ui.scoped((100.0, 20.0), Layout::Horizontal);
+    ///     ui.push((20.0, 20.0), Layout::Freeform);
+    ///     if fail {
+    ///         // both groups are popped
+    ///         return Err("oops");
+    ///     }
+    ///     ui.pop();
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 600.0), Layout::Vertical);
+    /// assert!(widget(&mut ui, true).is_err());
+    /// assert_eq!(ui.finish(), Ok(()));
+    /// ```
+    #[track_caller]
+    pub fn scoped(&mut self, size: impl Into<Vector>, layout: Layout) -> GroupGuard<'_, T> {
+        self.push(size, layout);
+        let depth = self.stack.len();
+        GroupGuard { ui: self, depth }
+    }
+
+    /// Pushes a group onto the group stack, calls `f` inside of it, and pops it. The result of `f` is returned.
+    ///
+    /// Like with [`Ui::scoped`], any groups left unpopped by `f` are popped along with the group.
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 600.0), Layout::Vertical);
+    /// let width = ui.group((100.0, 20.0), Layout::Horizontal, |ui| ui.width());
+    /// assert_eq!(width, 100.0);
+    /// assert_eq!(ui.cursor(), paws::vector(0.0, 20.0));
+    /// ```
+    #[track_caller]
+    pub fn group<R>(
+        &mut self,
+        size: impl Into<Vector>,
+        layout: Layout,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let mut guard = self.scoped(size, layout);
+        f(&mut guard)
+    }
+
+    /// Pops groups off the stack until only `depth` groups are left. The renderer state pushed by scroll areas is
+    /// popped, too.
+    fn unwind(&mut self, depth: usize) {
+        while self.stack.len() > depth {
+            let scroll = self.top().scroll;
+            expect_ok(self.pop_group());
+            if scroll {
+                match self.top().layer.buffer_index() {
+                    Some(index) => self.layers[index].push(DrawCommand::Pop),
+                    None => self.renderer.pop(),
+                }
+            }
         }
     }
 
@@ -339,28 +750,42 @@ impl<T: Renderer> Ui<T> {
     /// Pads the current group with some amount of padding.
     pub fn pad(&mut self, padding: impl Into<Padding>) {
         let padding = padding.into();
-        let rect = &mut self.top_mut().rect;
-        rect.position.x += padding.left;
-        rect.position.y += padding.top;
-        rect.size.x -= padding.left + padding.right;
-        rect.size.y -= padding.top + padding.bottom;
+        let top = self.top_mut();
+        top.rect.position.x += padding.left;
+        top.rect.position.y += padding.top;
+        top.rect.size.x -= padding.left + padding.right;
+        top.rect.size.y -= padding.top + padding.bottom;
+        top.resize_grid();
     }
 
-    /// Aligns the current group in the parent group, with the provided alignment.
+    /// Aligns the current group in the parent group, with the provided alignment. If the parent group uses the
+    /// grid layout, the current group is aligned inside of its cell instead.
     ///
     /// # Panics
     /// If there are less than two groups (the parent and the subject) on the stack.
     pub fn align(&mut self, alignment: Alignment) {
-        let parent = self
-            .stack
-            .get(self.stack.len() - 2)
-            .expect("no parent group on the stack to align to")
-            .rect;
-        let subject = &mut self
-            .stack
-            .last_mut()
-            .expect("no group on the stack to align")
-            .rect;
+        expect_ok(self.try_align(alignment));
+    }
+
+    /// Aligns the current group in the parent group, as described in [`Ui::align`].
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::MissingParent`] if the current group is the root group.
+    pub fn try_align(&mut self, alignment: Alignment) -> Result<(), UiError> {
+        let parent = match self.stack.len() {
+            0 => return Err(UiError::EmptyStack),
+            1 => return Err(UiError::MissingParent),
+            len => &self.stack[len - 2],
+        };
+        let parent = match &parent.grid {
+            Some(grid) if parent.layout == Layout::Grid => {
+                let cell = grid.current_rect();
+                Rect::new(parent.rect.position + cell.position, cell.size)
+            }
+            _ => parent.rect,
+        };
+        let subject = &mut self.try_top_mut()?.rect;
         subject.position.x = match alignment.0 {
             Left => parent.left(),
             Center => parent.center_x() - subject.width() / 2.0,
@@ -371,174 +796,1177 @@ impl<T: Renderer> Ui<T> {
             Middle => parent.center_y() - subject.height() / 2.0,
             Bottom => parent.bottom() - subject.height(),
         };
+        Ok(())
     }
 
     /// Inserts empty space between subgroups, by increasing or decreasing the cursor position by the given amount.
     ///
     /// # Panics
     ///  - If there are no groups.
-    ///  - On freeform layout, as it's not clear which direction the spacing should be performed in.
+    ///  - On freeform and grid layouts, as it's not clear which direction the spacing should be performed in.
     pub fn space(&mut self, amount: f32) {
-        let top = self.top_mut();
+        expect_ok(self.try_space(amount));
+    }
+
+    /// Inserts empty space between subgroups, as described in [`Ui::space`].
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::InvalidLayout`] on freeform and grid layouts.
+    pub fn try_space(&mut self, amount: f32) -> Result<(), UiError> {
+        let top = self.try_top_mut()?;
         match top.layout {
-            Layout::Freeform => panic!("using space() on Freeform layout is forbidden"),
+            layout @ Layout::Freeform | layout @ Layout::Grid => {
+                return Err(UiError::InvalidLayout {
+                    operation: "space",
+                    layout,
+                })
+            }
             Layout::Horizontal => top.cursor.x += amount,
             Layout::Vertical => top.cursor.y += amount,
             Layout::HorizontalRev => top.cursor.x -= amount,
             Layout::VerticalRev => top.cursor.y -= amount,
+            Layout::HorizontalWrap => top.cursor.x += amount,
+            Layout::VerticalWrap => top.cursor.y += amount,
+        }
+        Ok(())
+    }
+
+    /// Sets the gaps between subgroups in wrapping and grid layouts. In wrapping layouts, `main` is the gap between
+    /// subgroups in a single line, and `cross` is the gap between lines. In grid layouts, `main` is the gap between
+    /// columns, and `cross` is the gap between rows. On other layouts, the gaps have no effect.
+    pub fn set_gap(&mut self, main: f32, cross: f32) {
+        let top = self.top_mut();
+        top.main_gap = main;
+        top.cross_gap = cross;
+        top.resize_grid();
+    }
+
+    /// Returns the bounding box of all children popped off the current group so far, in absolute coordinates.
+    /// If no children have been popped yet, returns `None`.
+    pub fn content_rect(&self) -> Option<Rect> {
+        self.top().content
+    }
+
+    /// Returns the size of the current group's content. This is the distance from the group's layout starting corner
+    /// (eg. the upper-right corner in `HorizontalRev` layout) to the farthest edges of the children popped off the
+    /// group so far, so any space between the starting corner and the children is included. If no children have
+    /// been popped yet, returns (0, 0).
+    ///
+    /// Unlike [`Ui::cursor`], this takes into account the size of children along both axes, so it can be used for
+    /// sizing scroll areas and panels that shrink to fit their content.
+    pub fn content_size(&self) -> Vector {
+        let top = self.top();
+        let content = match top.content {
+            Some(content) => content,
+            None => return vector(0.0, 0.0),
+        };
+        let width = match top.layout {
+            Layout::HorizontalRev => top.rect.right() - content.left(),
+            _ => content.right() - top.rect.left(),
+        };
+        let height = match top.layout {
+            Layout::VerticalRev => top.rect.bottom() - content.top(),
+            _ => content.bottom() - top.rect.top(),
+        };
+        vector(width.max(0.0), height.max(0.0))
+    }
+
+    /// Returns the size that tightly encloses the current group's children. Along the main axis, this is where the
+    /// cursor ended up; along the cross axis, this is the content size.
+    fn fitted_size(&self) -> Vector {
+        let top = self.top();
+        match top.layout {
+            Layout::Freeform => top.cursor,
+            Layout::Horizontal => vector(top.cursor.x, self.content_size().y),
+            Layout::Vertical => vector(self.content_size().x, top.cursor.y),
+            Layout::HorizontalRev => vector(-top.cursor.x, self.content_size().y),
+            Layout::VerticalRev => vector(self.content_size().x, -top.cursor.y),
+            Layout::HorizontalWrap => vector(self.content_size().x, top.cursor.y + top.line_extent),
+            Layout::VerticalWrap => vector(top.cursor.x + top.line_extent, self.content_size().y),
+            Layout::Grid => match &top.grid {
+                Some(grid) => grid.size(),
+                None => top.rect.size,
+            },
+        }
+    }
+
+    /// Resizes the current group to the given width, keeping the right edge in place on `HorizontalRev` layout.
+    fn resize_width(&mut self, width: f32) {
+        let top = self.top_mut();
+        if top.layout == Layout::HorizontalRev {
+            top.rect.position.x += top.rect.width() - width;
+        }
+        top.rect.size.x = width;
+    }
+
+    /// Resizes the current group to the given height, keeping the bottom edge in place on `VerticalRev` layout.
+    fn resize_height(&mut self, height: f32) {
+        let top = self.top_mut();
+        if top.layout == Layout::VerticalRev {
+            top.rect.position.y += top.rect.height() - height;
         }
+        top.rect.size.y = height;
     }
 
     /// Resizes the current group to fit its children. This function considers a few cases:
     ///  - on `Freeform` layout, it sets the width and height to the cursor,
     ///  - on `Horizontal` layout, it sets the width to the cursor's X position,
     ///  - on `Vertical` layout, it sets the height to the cursor's Y position.
-    ///  - on reversed layouts, it panics, as layouting there works _a bit backwards_ and fitting currently doesn't work
-    ///    properly. This might get solved in a future release.
+    ///  - on `HorizontalRev` layout, it sets the width to the distance the cursor traveled, keeping the group's right
+    ///    edge in place,
+    ///  - on `VerticalRev` layout, it sets the height to the distance the cursor traveled, keeping the group's bottom
+    ///    edge in place,
+    ///  - on `HorizontalWrap` layout, it sets the height to the bottom of the last line,
+    ///  - on `VerticalWrap` layout, it sets the width to the right side of the last column,
+    ///  - on `Grid` layout, it sets the width and height to the total size of the grid's tracks.
+    ///
+    /// Note that apart from the freeform and grid layouts, this only fits the group along a single axis. To also take
+    /// the size of children along the other axis into account, use [`Ui::fit_both`].
     ///
     /// # Panics
-    ///  - If there are no groups.
-    ///  - On reversed layouts, as noted above.
+    /// If there are no groups.
     pub fn fit(&mut self) {
-        let top = self.top_mut();
-        match top.layout {
-            Layout::Freeform => top.rect.size = top.cursor,
-            Layout::Horizontal => top.rect.size.x = top.cursor.x,
-            Layout::Vertical => top.rect.size.y = top.cursor.y,
-            Layout::HorizontalRev | Layout::VerticalRev => {
-                panic!("reverse layout containers can't be fit()ted")
+        let size = self.fitted_size();
+        match self.top().layout {
+            Layout::Freeform | Layout::Grid => {
+                self.resize_width(size.x);
+                self.resize_height(size.y);
+            }
+            Layout::Horizontal | Layout::HorizontalRev | Layout::VerticalWrap => {
+                self.resize_width(size.x)
+            }
+            Layout::Vertical | Layout::VerticalRev | Layout::HorizontalWrap => {
+                self.resize_height(size.y)
             }
         }
     }
 
-    //
-    // internal getters
-    //
-
-    fn top(&self) -> &Group {
-        self.stack
-            .last()
-            .expect("no groups on the stack to read from. check your push() and pop()s")
+    /// Resizes the current group's width to fit its children. On layouts where the X axis is the cross axis, such as
+    /// `Vertical`, the width is set to the width of the widest child. Otherwise, this behaves like [`Ui::fit`].
+    ///
+    /// # Panics
+    /// If there are no groups.
+    pub fn fit_width(&mut self) {
+        let width = self.fitted_size().x;
+        self.resize_width(width);
     }
 
-    fn top_mut(&mut self) -> &mut Group {
-        self.stack
-            .last_mut()
-            .expect("no groups on the stack left to modify. check your push() and pop()s")
+    /// Resizes the current group's height to fit its children. On layouts where the Y axis is the cross axis, such as
+    /// `Horizontal`, the height is set to the height of the tallest child. Otherwise, this behaves like [`Ui::fit`].
+    ///
+    /// # Panics
+    /// If there are no groups.
+    pub fn fit_height(&mut self) {
+        let height = self.fitted_size().y;
+        self.resize_height(height);
     }
-}
 
-impl<T: Renderer> Ui<T> {
-    /// Allows one to draw in the current group by translating the renderer's matrix to the group's position.
-    /// The renderer can be obtained inside of the callback by using [`Ui::render`].
-    pub fn draw<F>(&mut self, do_draw: F)
-    where
-        F: FnOnce(&mut Self),
-    {
-        let translation = self.top().rect.position;
-        self.render().push();
-        self.render().translate(translation);
-        do_draw(self);
-        self.render().pop();
+    /// Resizes the current group to fit its children along both axes. This is equivalent to calling both
+    /// [`Ui::fit_width`] and [`Ui::fit_height`].
+    ///
+    /// # Panics
+    /// If there are no groups.
+    pub fn fit_both(&mut self) {
+        self.fit_width();
+        self.fit_height();
     }
 
-    /// Clips drawing to only occur inside of the current group.
+    /// Declares a flex plan for the children of the current group. The remaining space along the group's main axis
+    /// is distributed among the given items, as described in [`FlexItem`]'s documentation. Children can then be
+    /// pushed in order using [`Ui::push_flex`], or skipped over using [`Ui::flex_space`].
     ///
-    /// Any pixels outside of the group are discarded. Note that to undo the clip,
-    /// [`self.render().push()`][Renderer::push] and [`self.render().pop()`][Renderer::pop] must be used.
-    pub fn clip(&mut self) {
-        let rect = self.top().rect;
-        self.render().clip(rect);
+    /// ```
+    /// use paws::{FlexItem, Layout, NoRenderer, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 600.0), Layout::Horizontal);
+    /// ui.flex(&[FlexItem::fixed(200.0), FlexItem::grow(1.0), FlexItem::fixed(100.0)]);
+    /// ui.push_flex(Layout::Vertical); // sidebar
+    /// ui.pop();
+    /// ui.push_flex(Layout::Vertical); // content
+    /// assert_eq!(ui.width(), 500.0);
+    /// ui.pop();
+    /// ui.push_flex(Layout::Vertical); // status
+    /// ui.pop();
+    /// ```
+    ///
+    /// Declaring a new plan replaces the previous one.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - On freeform and grid layouts, as they don't have a main axis.
+    #[track_caller]
+    pub fn flex(&mut self, items: &[FlexItem]) {
+        expect_ok(self.try_flex(items));
     }
 
-    /// Draws a rectangle that fills the current group with the given color.
-    pub fn fill(&mut self, color: impl Into<Color>) {
-        self.fill_rounded(color, 0.0);
+    /// Declares the flex plan of the current group, as described in [`Ui::flex`].
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::InvalidLayout`] on freeform and grid layouts, as they don't have a main axis.
+    pub fn try_flex(&mut self, items: &[FlexItem]) -> Result<(), UiError> {
+        let available = match self.try_top()?.layout {
+            layout @ Layout::Freeform | layout @ Layout::Grid => {
+                return Err(UiError::InvalidLayout {
+                    operation: "flex",
+                    layout,
+                })
+            }
+            Layout::Horizontal | Layout::HorizontalRev | Layout::HorizontalWrap => {
+                self.remaining_width()
+            }
+            Layout::Vertical | Layout::VerticalRev | Layout::VerticalWrap => {
+                self.remaining_height()
+            }
+        };
+        let top = self.try_top_mut()?;
+        top.flex = resolve_flex(items, available);
+        top.next_flex = 0;
+        Ok(())
     }
 
-    /// Draws a rounded rectangle that fills the current group, with the given color and corner radius.
-    pub fn fill_rounded(&mut self, color: impl Into<Color>, radius: f32) {
-        let rect = self.top().rect;
-        self.render().fill(rect, color.into(), radius);
+    /// Takes the next size off the current group's flex plan.
+    fn next_flex(&mut self) -> f32 {
+        expect_ok(self.try_next_flex())
     }
 
-    /// Draws a rectangle outline that creates a border around the current group, with the given color and
-    /// line thickness.
-    pub fn outline(&mut self, color: impl Into<Color>, thickness: f32) {
-        self.outline_rounded(color, 0.0, thickness);
+    fn try_next_flex(&mut self) -> Result<f32, UiError> {
+        let top = self.try_top_mut()?;
+        let size = *top
+            .flex
+            .get(top.next_flex)
+            .ok_or(UiError::FlexPlanExhausted)?;
+        top.next_flex += 1;
+        Ok(size)
     }
 
-    /// Draws a rounded rectangle outline that creates a border around the current group, with the given color,
-    /// corner radius, and line thickness.
-    pub fn outline_rounded(&mut self, color: impl Into<Color>, radius: f32, thickness: f32) {
-        let rect = self.top().rect;
-        self.render().outline(rect, color.into(), radius, thickness);
+    /// Pushes a group whose size along the main axis is taken from the next item of the current group's flex plan,
+    /// and whose size along the cross axis spans the whole current group.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the flex plan declared using [`Ui::flex`] has no items left.
+    #[track_caller]
+    pub fn push_flex(&mut self, layout: Layout) {
+        expect_ok(self.try_push_flex(layout));
     }
 
-    /// Returns the current group's line cap.
-    pub fn line_cap(&self) -> LineCap {
-        self.top().line_cap
+    /// Pushes a group sized according to the next item of the current group's flex plan, as described in
+    /// [`Ui::push_flex`].
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::FlexPlanExhausted`] if all the items of the flex plan were already used.
+    #[track_caller]
+    pub fn try_push_flex(&mut self, layout: Layout) -> Result<(), UiError> {
+        let size = self.try_next_flex()?;
+        let size = match self.top().layout {
+            Layout::Horizontal | Layout::HorizontalRev | Layout::HorizontalWrap => {
+                vector(size, self.height())
+            }
+            _ => vector(self.width(), size),
+        };
+        self.try_push(size, layout)
     }
 
-    /// Sets the current group's line cap for rendering lines. The root group's default line cap is [`LineCap::Butt`].
-    pub fn set_line_cap(&mut self, new_line_cap: LineCap) {
-        self.top_mut().line_cap = new_line_cap;
+    /// Inserts empty space between subgroups, whose size is taken from the next item of the current group's flex
+    /// plan.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the flex plan declared using [`Ui::flex`] has no items left.
+    pub fn flex_space(&mut self) {
+        let size = self.next_flex();
+        self.space(size);
     }
 
-    /// Helper function for drawing borders around the current group.
-    fn border(&mut self, a: Point, b: Point, color: Color, thickness: f32) {
-        let line_cap = self.top().line_cap;
-        self.render().line(a, b, color, line_cap, thickness);
+    /// Declares the column and row tracks of the current group, which must use the [`Layout::Grid`] layout.
+    /// Subgroups pushed afterwards are placed in consecutive cells, row by row. If more rows are needed than were
+    /// declared, implicit [`Track::Auto`] rows are added. The tracks fill the group's size, and follow it if the group
+    /// is padded using [`Ui::pad`] or its gaps are changed using [`Ui::set_gap`] later.
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, Track, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 600.0), Layout::Grid);
+    /// ui.set_gap(8.0, 4.0);
+    /// ui.grid(&[Track::Fixed(120.0), Track::Fraction(1.0)], &[]);
+    /// for _ in 0..3 {
+    ///     ui.push((100.0, 20.0), Layout::Freeform); // label
+    ///     ui.pop();
+    ///     ui.push_cell(Layout::Freeform); // value
+    ///     ui.pop();
+    /// }
+    /// ui.fit();
+    /// assert_eq!(ui.size(), paws::vector(800.0, 68.0));
+    /// ```
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the current group doesn't use the grid layout.
+    ///  - If no columns are given.
+    pub fn grid(&mut self, columns: &[Track], rows: &[Track]) {
+        let top = self.top_mut();
+        assert!(
+            top.layout == Layout::Grid,
+            "grid() can only be used on Grid layout"
+        );
+        let gap = vector(top.main_gap, top.cross_gap);
+        top.grid = Some(Grid::new(columns, rows, top.rect.size, gap));
     }
 
-    /// Draws a line spanning the left side of the current group, with the given color and line thickness.
-    pub fn border_left(&mut self, color: impl Into<Color>, thickness: f32) {
-        let rect = self.top().rect;
-        self.border(rect.top_left(), rect.bottom_left(), color.into(), thickness);
+    fn grid_mut(&mut self) -> &mut Grid {
+        self.top_mut()
+            .grid
+            .as_mut()
+            .expect("grid layout used without declaring tracks. check your grid()")
     }
 
-    /// Draws a line spanning the top side of the current group, with the given color and line thickness.
-    pub fn border_top(&mut self, color: impl Into<Color>, thickness: f32) {
-        let rect = self.top().rect;
-        self.border(rect.top_left(), rect.top_right(), color.into(), thickness);
+    /// Places the next subgroup pushed onto the current grid group at the given column and row, instead of the next
+    /// free cell. Automatic placement continues after this cell.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the current group's grid tracks haven't been declared.
+    ///  - If the column is out of bounds.
+    pub fn set_cell(&mut self, column: usize, row: usize) {
+        self.grid_mut().set_cell(column, row);
     }
 
-    /// Draws a line spanning the right side of the current group, with the given color and line thickness.
-    pub fn border_right(&mut self, color: impl Into<Color>, thickness: f32) {
-        let rect = self.top().rect;
-        self.border(
-            rect.top_right(),
-            rect.bottom_right(),
-            color.into(),
-            thickness,
-        );
+    /// Makes the next subgroup pushed onto the current grid group span the given amount of columns and rows.
+    /// The column span is limited to the number of columns in the grid.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the current group's grid tracks haven't been declared.
+    ///  - If either span is zero.
+    pub fn set_span(&mut self, columns: usize, rows: usize) {
+        self.grid_mut().set_span(columns, rows);
     }
 
-    /// Draws a line spanning the bottom side of the current group, with the given color and line thickness.
-    pub fn border_bottom(&mut self, color: impl Into<Color>, thickness: f32) {
-        let rect = self.top().rect;
-        self.border(
-            rect.bottom_left(),
-            rect.bottom_right(),
-            color.into(),
-            thickness,
-        );
+    /// Returns the size of the cell the next subgroup will be placed in, on the current grid group. Auto tracks
+    /// that don't have any subgroups yet have a size of zero.
+    ///
+    /// # Panics
+    ///  - If there are no groups.
+    ///  - If the current group's grid tracks haven't been declared.
+    pub fn cell_size(&self) -> Vector {
+        self.top()
+            .grid
+            .as_ref()
+            .expect("grid layout used without declaring tracks. check your grid()")
+            .next_rect()
+            .size
     }
 
-    /// Draws text inside of the current group, with the given color and alignment inside of the group's rectangle.
+    /// Pushes a group that fills the next cell of the current grid group.
     ///
     /// # Panics
-    /// If there are no groups on the stack, or if a font isn't set.
-    pub fn text(
-        &mut self,
-        font: &T::Font,
-        text: &str,
-        color: impl Into<Color>,
-        alignment: Alignment,
-    ) {
-        let rect = self.top().rect;
-        self.render()
-            .text(rect, font, text, color.into(), alignment);
+    ///  - If there are no groups.
+    ///  - If the current group's grid tracks haven't been declared.
+    #[track_caller]
+    pub fn push_cell(&mut self, layout: Layout) {
+        expect_ok(self.try_push_cell(layout));
+    }
+
+    /// Pushes a group that fills the next cell of the current grid group.
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::MissingGrid`] if the current group's grid tracks haven't been declared.
+    #[track_caller]
+    pub fn try_push_cell(&mut self, layout: Layout) -> Result<(), UiError> {
+        let grid = self.try_top()?.grid.as_ref().ok_or(UiError::MissingGrid)?;
+        let size = grid.next_rect().size;
+        self.try_push(size, layout)
+    }
+
+    /// Pushes a scroll area onto the group stack. This pushes two groups: the _viewport_, with the given size, and the
+    /// _content_ group inside of it, with the same size and the given layout. The content group is offset by the
+    /// scroll state's offset, and drawing is clipped to the viewport, so only the visible part of the content is
+    /// shown.
+    ///
+    /// Because the renderer's clip region can only be undone by popping it off the renderer's stack, this also calls
+    /// [`Renderer::push`] before clipping. Thus, the scroll area must be popped using [`Ui::pop_scroll`] and not
+    /// [`Ui::pop`].
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, ScrollState, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// let mut scroll = ScrollState::new();
+    /// scroll.offset.y = 50.0;
+    ///
+    /// ui.root((800.0, 600.0), Layout::Vertical);
+    /// ui.push_scroll((200.0, 100.0), Layout::Vertical, &scroll);
+    /// for _ in 0..10 {
+    ///     ui.push((ui.width(), 20.0), Layout::Freeform);
+    ///     ui.pop();
+    /// }
+    /// ui.pop_scroll(&mut scroll);
+    ///
+    /// assert_eq!(scroll.content_size, paws::vector(200.0, 200.0));
+    /// assert_eq!(scroll.max_offset(), paws::vector(0.0, 100.0));
+    /// ```
+    #[track_caller]
+    pub fn push_scroll(&mut self, size: impl Into<Vector>, layout: Layout, state: &ScrollState) {
+        let size = size.into();
+        self.push(size, Layout::Freeform);
+        self.painter().push();
+        self.clip();
+        self.set_cursor(-state.offset);
+        self.push(size, layout);
+        self.top_mut().scroll = true;
+    }
+
+    /// Pops a scroll area off the group stack, updating the scroll state's content and viewport sizes. The content
+    /// size is measured using [`Ui::content_size`]. Note that this doesn't clamp the scroll offset, so if the content
+    /// shrinks, [`ScrollState::clamp`] should be used to prevent scrolling past the content.
+    ///
+    /// # Panics
+    /// If the current group isn't the content of a scroll area pushed with [`Ui::push_scroll`].
+    #[track_caller]
+    pub fn pop_scroll(&mut self, state: &mut ScrollState) {
+        expect_ok(self.try_pop_scroll(state));
+    }
+
+    /// Pops a scroll area off the group stack, as described in [`Ui::pop_scroll`].
+    ///
+    /// # Errors
+    ///  - [`UiError::EmptyStack`] if there are no groups on the stack.
+    ///  - [`UiError::NotScrollArea`] if the current group isn't the content of a scroll area.
+    pub fn try_pop_scroll(&mut self, state: &mut ScrollState) -> Result<(), UiError> {
+        if !self.try_top()?.scroll {
+            return Err(UiError::NotScrollArea);
+        }
+        state.content_size = self.content_size();
+        self.pop_group()?;
+        state.viewport_size = self.size();
+        self.painter().pop();
+        self.try_pop()
+    }
+
+    //
+    // text measurement
+    //
+
+    /// Measures a single line of text using the renderer, without drawing it.
+    pub fn measure_text(&self, font: &T::Font, text: &str) -> TextMetrics {
+        self.renderer.measure_text(font, text)
+    }
+
+    /// Returns the size of a single line of text, with the given padding added around it.
+    pub fn text_size(&self, font: &T::Font, text: &str, padding: impl Into<Padding>) -> Vector {
+        let padding = padding.into();
+        self.measure_text(font, text).size()
+            + vector(padding.left + padding.right, padding.top + padding.bottom)
+    }
+
+    /// Pushes a group sized to fit a single line of text, with the given padding added around it. This is useful for
+    /// auto-sizing labels and buttons.
+    ///
+    /// The group itself is not padded, so that backgrounds drawn into it cover the padding. With even padding, text
+    /// drawn with [`CENTER`] alignment lands exactly inside of the padding:
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, NoRendererFont, Ui, CENTER};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((800.0, 32.0), Layout::Horizontal);
+    /// for label in &["Open", "Save", "Close"] {
+    ///     ui.push_text(&NoRendererFont, label, 8.0, Layout::Freeform);
+    ///     ui.fill(paws::rgb(64, 64, 64));
+    ///     ui.text(&NoRendererFont, label, paws::rgb(255, 255, 255), CENTER);
+    ///     ui.pop();
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// If there are no groups on the stack.
+    #[track_caller]
+    pub fn push_text(
+        &mut self,
+        font: &T::Font,
+        text: &str,
+        padding: impl Into<Padding>,
+        layout: Layout,
+    ) {
+        let size = self.text_size(font, text, padding);
+        self.push(size, layout);
+    }
+
+    /// Lays out multi-line text inside of the given rectangle, without drawing it. See [`TextOptions`] for how the
+    /// text is broken into lines and positioned.
+    pub fn layout_text(
+        &self,
+        font: &T::Font,
+        text: &str,
+        rect: Rect,
+        options: TextOptions,
+    ) -> TextLayout {
+        let line_height = self.renderer.measure_text(font, "").line_height;
+        layout_text(rect, text, options, line_height, |line| {
+            self.renderer.measure_text(font, line).width
+        })
+    }
+
+    /// Returns the size of multi-line text laid out with the given maximum width. The height isn't limited, so only
+    /// [`TextOptions::max_lines`] can cause lines to be left out. This is useful for sizing a group to fit a
+    /// paragraph before pushing it.
+    pub fn paragraph_size(
+        &self,
+        font: &T::Font,
+        text: &str,
+        width: f32,
+        options: TextOptions,
+    ) -> Vector {
+        let rect = Rect::new((0.0, 0.0), (width, f32::INFINITY));
+        let options = TextOptions {
+            alignment: (options.alignment.0, Top),
+            ..options
+        };
+        self.layout_text(font, text, rect, options).size
+    }
+
+    /// Lays out spans of rich text inside of the given rectangle, without drawing them. The spans are laid out as a
+    /// single paragraph, as described by [`TextOptions`].
+    pub fn layout_rich_text(
+        &self,
+        spans: &[TextSpan<'_, T::Font>],
+        rect: Rect,
+        options: TextOptions,
+    ) -> RichTextLayout {
+        layout_rich_text(rect, spans, options, |font, text| {
+            self.renderer.measure_text(font, text)
+        })
+    }
+
+    //
+    // IDs
+    //
+
+    /// Creates an ID for a widget by hashing the given key with the current ID scope. The key can be anything
+    /// hashable, such as a string or a loop index.
+    ///
+    /// # Panics
+    /// In debug builds, if the same ID was already created during this frame. This usually means that two widgets
+    /// were given the same key in the same scope; use [`Ui::push_id`] to tell them apart.
+    pub fn id(&mut self, key: impl Hash) -> Id {
+        let id = self.current_id().with(key);
+        #[cfg(debug_assertions)]
+        assert!(
+            self.seen_ids.insert(id),
+            "duplicate widget ID {:?} in a single frame. check your id() keys, or use push_id() to separate scopes",
+            id
+        );
+        id
+    }
+
+    /// Returns the ID of the current ID scope.
+    pub fn current_id(&self) -> Id {
+        *self
+            .ids
+            .last()
+            .expect("no ID scopes on the stack. did you forget root()?")
+    }
+
+    /// Pushes a new ID scope, derived from the current scope and the given key. IDs created while the scope is active
+    /// are derived from it, until it's popped with [`Ui::pop_id`] or the current group is popped.
+    pub fn push_id(&mut self, key: impl Hash) {
+        let id = self.current_id().with(key);
+        self.ids.push(id);
+    }
+
+    /// Returns the persistent state of type `S` for the widget with the given ID, creating it with `S::default()` if
+    /// it doesn't exist yet. Each ID can hold one value of every type.
+    ///
+    /// The state persists across frames, as long as it's accessed at least once per frame. Otherwise, it's dropped
+    /// upon the next call to [`Ui::root`].
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, Ui};
+    ///
+    /// #[derive(Default)]
+    /// struct Header {
+    ///     expanded: bool,
+    /// }
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// for frame in 0..3 {
+    ///     ui.root((800.0, 600.0), Layout::Vertical);
+    ///     let id = ui.id("header");
+    ///     let header = ui.state::<Header>(id);
+    ///     // the state is kept from the previous frame
+    ///     assert_eq!(header.expanded, frame % 2 == 1);
+    ///     header.expanded = !header.expanded;
+    /// }
+    /// ```
+    pub fn state<S: Any + Default>(&mut self, id: Id) -> &mut S {
+        self.state.get_or_default(id)
+    }
+
+    /// Pops an ID scope off the ID stack.
+    ///
+    /// # Panics
+    /// If no ID scopes were pushed inside of the current group.
+    pub fn pop_id(&mut self) {
+        assert!(
+            self.ids.len() > self.top().id_depth,
+            "pop_id() used without a matching push_id() in the current group. check your push_id() and pop_id()s"
+        );
+        self.ids.pop();
+    }
+
+    //
+    // internal getters
+    //
+
+    fn top(&self) -> &Group {
+        self.stack
+            .last()
+            .expect("no groups on the stack to read from. check your push() and pop()s")
+    }
+
+    fn top_mut(&mut self) -> &mut Group {
+        self.stack
+            .last_mut()
+            .expect("no groups on the stack left to modify. check your push() and pop()s")
+    }
+
+    fn try_top(&self) -> Result<&Group, UiError> {
+        self.stack.last().ok_or(UiError::EmptyStack)
+    }
+
+    fn try_top_mut(&mut self) -> Result<&mut Group, UiError> {
+        self.stack.last_mut().ok_or(UiError::EmptyStack)
+    }
+}
+
+/// Unwraps the result of a `try_*` method, panicking with the error's message if it failed.
+#[track_caller]
+fn expect_ok<T>(result: Result<T, UiError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+impl<T: Renderer> Ui<T> {
+    /// Returns the current group's layer.
+    pub fn layer(&self) -> Layer {
+        self.top().layer
+    }
+
+    /// Returns a renderer that draws onto the current group's layer. Inside of groups on layers other than
+    /// [`Layer::Normal`], this should be used instead of [`Ui::render`].
+    pub fn painter(&mut self) -> Painter<'_, T> {
+        let layer = self.top().layer;
+        Painter {
+            renderer: &mut self.renderer,
+            buffer: match layer.buffer_index() {
+                Some(index) => Some((
+                    &mut self.layers[index],
+                    self.cloner
+                        .expect("groups can only be moved onto deferred layers using set_layer()"),
+                )),
+                None => None,
+            },
+        }
+    }
+
+    /// Draws the buffered contents of all layers in order, and clears the buffers. This should be called at the end
+    /// of every frame, after all groups are done drawing.
+    pub fn flush_layers(&mut self) {
+        for buffer in &mut self.layers {
+            for command in buffer.drain(..) {
+                command.execute(&mut self.renderer);
+            }
+        }
+    }
+}
+
+impl<T> Ui<T>
+where
+    T: Renderer,
+    T::Font: Clone,
+    T::Image: Clone,
+{
+    /// Moves the current group and its children onto the given layer. The root group is on [`Layer::Normal`].
+    ///
+    /// When moving onto a higher layer, the clip region used for hit-testing is reset, as popups usually escape the
+    /// bounds of their parents. Note that this doesn't affect clipping done by the renderer, but draw calls on
+    /// deferred layers are flushed after the normal layer is done drawing, so they don't inherit its clip regions
+    /// either.
+    ///
+    /// Because draw calls on deferred layers are buffered, moving groups onto layers requires the renderer's font
+    /// and image types to implement `Clone`. Drawing onto the normal layer has no such requirement.
+    pub fn set_layer(&mut self, new_layer: Layer) {
+        self.cloner = Some(Cloner::new());
+        let root_rect = self.root_rect();
+        let top = self.top_mut();
+        if new_layer > top.layer {
+            top.clip = root_rect;
+        }
+        top.layer = new_layer;
+    }
+}
+
+impl<T: Renderer> Ui<T> {
+    /// Allows one to draw in the current group by translating the renderer's matrix to the group's position.
+    /// The renderer can be obtained inside of the callback by using [`Ui::painter`], or [`Ui::render`] if the
+    /// group is on the normal layer.
+    pub fn draw<F>(&mut self, do_draw: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let translation = self.top().rect.position;
+        self.painter().push();
+        self.painter().translate(translation);
+        do_draw(self);
+        self.painter().pop();
+    }
+
+    /// Clips drawing to only occur inside of the current group.
+    ///
+    /// Any pixels outside of the group are discarded. Note that to undo the clip,
+    /// [`self.render().push()`][Renderer::push] and [`self.render().pop()`][Renderer::pop] must be used.
+    ///
+    /// The clip region is also remembered for hit-testing, so that the clipped-out parts of the current group and
+    /// its children don't receive any input. This lasts until the current group is popped.
+    pub fn clip(&mut self) {
+        let top = self.top_mut();
+        top.clip = top.clip.intersection(top.rect);
+        let rect = top.rect;
+        self.painter().clip(rect);
+    }
+
+    /// Draws a rectangle that fills the current group with the given color.
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        self.fill_rounded(color, 0.0);
+    }
+
+    /// Draws a rounded rectangle that fills the current group, with the given color and corner radius.
+    pub fn fill_rounded(&mut self, color: impl Into<Color>, radius: f32) {
+        let rect = self.top().rect;
+        self.painter().fill(rect, color.into(), radius);
+    }
+
+    /// Draws a rectangle outline that creates a border around the current group, with the given color and
+    /// line thickness.
+    pub fn outline(&mut self, color: impl Into<Color>, thickness: f32) {
+        self.outline_rounded(color, 0.0, thickness);
+    }
+
+    /// Draws a rounded rectangle outline that creates a border around the current group, with the given color,
+    /// corner radius, and line thickness.
+    pub fn outline_rounded(&mut self, color: impl Into<Color>, radius: f32, thickness: f32) {
+        let rect = self.top().rect;
+        self.painter()
+            .outline(rect, color.into(), radius, thickness);
+    }
+
+    /// Returns the current group's line cap.
+    pub fn line_cap(&self) -> LineCap {
+        self.top().line_cap
+    }
+
+    /// Sets the current group's line cap for rendering lines. The root group's default line cap is [`LineCap::Butt`].
+    pub fn set_line_cap(&mut self, new_line_cap: LineCap) {
+        self.top_mut().line_cap = new_line_cap;
+    }
+
+    /// Helper function for drawing borders around the current group.
+    fn border(&mut self, a: Point, b: Point, color: Color, thickness: f32) {
+        let line_cap = self.top().line_cap;
+        self.painter().line(a, b, color, line_cap, thickness);
+    }
+
+    /// Draws a line spanning the left side of the current group, with the given color and line thickness.
+    pub fn border_left(&mut self, color: impl Into<Color>, thickness: f32) {
+        let rect = self.top().rect;
+        self.border(rect.top_left(), rect.bottom_left(), color.into(), thickness);
+    }
+
+    /// Draws a line spanning the top side of the current group, with the given color and line thickness.
+    pub fn border_top(&mut self, color: impl Into<Color>, thickness: f32) {
+        let rect = self.top().rect;
+        self.border(rect.top_left(), rect.top_right(), color.into(), thickness);
+    }
+
+    /// Draws a line spanning the right side of the current group, with the given color and line thickness.
+    pub fn border_right(&mut self, color: impl Into<Color>, thickness: f32) {
+        let rect = self.top().rect;
+        self.border(
+            rect.top_right(),
+            rect.bottom_right(),
+            color.into(),
+            thickness,
+        );
+    }
+
+    /// Draws a line spanning the bottom side of the current group, with the given color and line thickness.
+    pub fn border_bottom(&mut self, color: impl Into<Color>, thickness: f32) {
+        let rect = self.top().rect;
+        self.border(
+            rect.bottom_left(),
+            rect.bottom_right(),
+            color.into(),
+            thickness,
+        );
+    }
+
+    /// Draws an image inside of the current group, fit into the group's rectangle using the given fit mode. The
+    /// image's colors are multiplied by the tint, so [`Color::WHITE`] draws the image as is.
+    pub fn image(&mut self, image: &T::Image, fit: ImageFit, tint: impl Into<Color>) {
+        let rect = self.top().rect;
+        let image_size = self.renderer.image_size(image);
+        if let Some((rect, source)) = fit_image(rect, image_size, fit) {
+            self.painter().image(rect, image, source, tint.into());
+        }
+    }
+
+    /// Draws a nine-slice image filling the current group. The insets split the image into nine parts: the corners
+    /// are drawn unscaled, the edges are stretched along one axis, and the center is stretched or tiled to fill the
+    /// rest of the group. This is useful for skinning panels and buttons with textures.
+    ///
+    /// See [`Renderer::nine_slice`] for details.
+    pub fn nine_slice(
+        &mut self,
+        image: &T::Image,
+        insets: impl Into<Padding>,
+        center: NineSliceCenter,
+        tint: impl Into<Color>,
+    ) {
+        let rect = self.top().rect;
+        self.painter()
+            .nine_slice(rect, image, insets.into(), center, tint.into());
+    }
+
+    /// Lays out and draws multi-line text inside of the current group, with the given color. Returns the layout,
+    /// whose size can be used to fit the group to the text.
+    ///
+    /// See [`TextOptions`] for how the text is broken into lines and positioned.
+    pub fn paragraph(
+        &mut self,
+        font: &T::Font,
+        text: &str,
+        color: impl Into<Color>,
+        options: TextOptions,
+    ) -> TextLayout {
+        let rect = self.top().rect;
+        let color = color.into();
+        let layout = self.layout_text(font, text, rect, options);
+        let mut painter = self.painter();
+        for line in &layout.lines {
+            // lines are drawn into rows spanning the whole group, so that differences between the measured and
+            // drawn width of the text don't throw off the alignment
+            let row = Rect::new(
+                (rect.left(), line.rect.top()),
+                (rect.width(), line.rect.height()),
+            );
+            painter.text(row, font, &line.text, color, (options.alignment.0, Middle));
+        }
+        layout
+    }
+
+    /// Lays out and draws spans of rich text inside of the current group. The spans are laid out as a single
+    /// paragraph, as described by [`TextOptions`]; each line is as tall as the tallest font used on it, and the spans
+    /// on a line share a common baseline. Underlines and strikethroughs are drawn as lines 1 unit thick, in the
+    /// span's color.
+    ///
+    /// Returns the layout, which can be used to fit the group to the text, or to hit-test spans:
+    ///
+    /// ```
+    /// use paws::{Layout, NoRenderer, NoRendererFont, TextOptions, TextSpan, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// ui.root((400.0, 300.0), Layout::Freeform);
+    /// let font = NoRendererFont;
+    /// let layout = ui.rich_text(
+    ///     &[
+    ///         TextSpan::new("[error] ", &font, paws::rgb(255, 64, 64)),
+    ///         TextSpan::new("see the ", &font, paws::rgb(255, 255, 255)),
+    ///         TextSpan::new("docs", &font, paws::rgb(64, 128, 255)).underline(),
+    ///     ],
+    ///     TextOptions::default(),
+    /// );
+    /// if let Some(pointer) = ui.input().pointer() {
+    ///     if layout.span_at(pointer) == Some(2) {
+    ///         // the link is hovered
+    ///     }
+    /// }
+    /// ```
+    pub fn rich_text(
+        &mut self,
+        spans: &[TextSpan<'_, T::Font>],
+        options: TextOptions,
+    ) -> RichTextLayout {
+        let rect = self.top().rect;
+        let layout = self.layout_rich_text(spans, rect, options);
+        let mut painter = self.painter();
+        for piece in &layout.pieces {
+            let span = &spans[piece.span];
+            let metrics = painter.measure_text(span.font, "");
+            let (left, right) = (piece.rect.left(), piece.rect.right());
+            let glyphs = Rect::new(
+                (left, piece.baseline - metrics.ascent),
+                (piece.rect.width(), metrics.ascent + metrics.descent),
+            );
+            painter.text(glyphs, span.font, &piece.text, span.color, (Left, Middle));
+            if span.underline {
+                let y = piece.baseline + metrics.descent / 2.0;
+                painter.line(
+                    point(left, y),
+                    point(right, y),
+                    span.color,
+                    LineCap::Butt,
+                    1.0,
+                );
+            }
+            if span.strikethrough {
+                let y = piece.baseline - metrics.ascent / 3.0;
+                painter.line(
+                    point(left, y),
+                    point(right, y),
+                    span.color,
+                    LineCap::Butt,
+                    1.0,
+                );
+            }
+        }
+        layout
+    }
+
+    /// Draws text inside of the current group, with the given color and alignment inside of the group's rectangle.
+    ///
+    /// # Panics
+    /// If there are no groups on the stack, or if a font isn't set.
+    pub fn text(
+        &mut self,
+        font: &T::Font,
+        text: &str,
+        color: impl Into<Color>,
+        alignment: Alignment,
+    ) {
+        let rect = self.top().rect;
+        self.painter()
+            .text(rect, font, text, color.into(), alignment);
+    }
+}
+
+impl<T: Renderer> Ui<T> {
+    /// Sets the input state snapshot for the current frame. This should be called at the start of every frame, after
+    /// [`Ui::root`].
+    ///
+    /// For every time Tab was pressed during this frame, focus is moved to the next focusable widget (or the previous
+    /// one, if Shift is held).
+    pub fn set_input(&mut self, input: Input) {
+        self.input = input;
+        for _ in 0..input.key_presses(Key::Tab) {
+            self.focus.navigate(!input.modifiers().shift);
+        }
+    }
+
+    /// Returns the input state snapshot for the current frame.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Returns the region of the current group that can receive input. This is the intersection of the current
+    /// group's rectangle and the clip regions set using [`Ui::clip`] in this group and its parents, in absolute
+    /// coordinates.
+    pub fn hit_rect(&self) -> Rect {
+        let top = self.top();
+        top.clip.intersection(top.rect)
+    }
+
+    /// Returns whether the pointer is inside of the current group, and not clipped out or covered by a group on a
+    /// higher layer. Covering groups are determined during the previous frame.
+    pub fn hovered(&self) -> bool {
+        match self.input.pointer() {
+            Some(pointer) => {
+                let layer = self.top().layer;
+                self.hit_rect().contains(pointer)
+                    && !self
+                        .blockers
+                        .iter()
+                        .any(|&(blocker, rect)| blocker > layer && rect.contains(pointer))
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether the given button was pressed during this frame while the pointer was over the current group.
+    pub fn pressed(&self, button: MouseButton) -> bool {
+        self.input.was_pressed(button) && self.hovered()
+    }
+
+    /// Returns whether the given button was released during this frame while the pointer was over the current group.
+    pub fn clicked(&self, button: MouseButton) -> bool {
+        self.input.was_released(button) && self.hovered()
+    }
+
+    /// Returns whether the given button is held down while the pointer is over the current group.
+    pub fn held(&self, button: MouseButton) -> bool {
+        self.input.is_down(button) && self.hovered()
+    }
+
+    /// Returns the scroll delta for this frame if the pointer is over the current group, or (0, 0) otherwise.
+    pub fn scrolled(&self) -> Vector {
+        if self.hovered() {
+            self.input.scroll()
+        } else {
+            vector(0.0, 0.0)
+        }
+    }
+
+    /// Registers the current group as the widget with the given ID, and returns how the pointer interacts with it.
+    ///
+    /// A widget becomes _hot_ when it's hovered, no other widget is active, and it was the topmost hovered widget
+    /// during the previous frame (widgets registered later are considered to be on top), so at most one widget is
+    /// hot at a time. Pressing `button` on a hot widget makes it _active_. If the pointer is pressed on a widget that
+    /// isn't hot yet, eg. because the pointer just moved onto it, the press activates the topmost hovered widget once
+    /// all widgets are known, that is upon the next call to [`Ui::root`], so the press is reported during the next
+    /// frame. The active widget captures the pointer: it stays active, and no other widget can become hot, until the
+    /// button is released, even if the pointer leaves the widget's group.
+    ///
+    /// ```
+    /// use paws::{Input, Layout, MouseButton, NoRenderer, Ui};
+    ///
+    /// let mut ui = Ui::new(NoRenderer);
+    /// let mut input = Input::new();
+    /// let mut value = 0.0;
+    ///
+    /// input.set_pointer(Some(paws::point(10.0, 10.0)));
+    /// for frame in 0..3 {
+    ///     // press inside of the slider, then drag the pointer out of it
+    ///     match frame {
+    ///         1 => input.press(MouseButton::Left),
+    ///         2 => input.set_pointer(Some(paws::point(150.0, 300.0))),
+    ///         _ => (),
+    ///     }
+    ///
+    ///     ui.root((800.0, 600.0), Layout::Vertical);
+    ///     ui.set_input(input);
+    ///     ui.push((200.0, 20.0), Layout::Freeform);
+    ///     let id = ui.id("slider");
+    ///     if ui.interact(id, MouseButton::Left).active {
+    ///         let pointer = ui.input().pointer().unwrap();
+    ///         value = (pointer.x - ui.position().x) / ui.width();
+    ///     }
+    ///     ui.pop();
+    ///
+    ///     input.next_frame();
+    /// }
+    /// assert_eq!(value, 0.75);
+    /// ```
+    pub fn interact(&mut self, id: Id, button: MouseButton) -> Interaction {
+        let hovered = self.hovered();
+        let captured = matches!(self.active, Some((active, _)) if active != id);
+        let layer = self.top().layer;
+        if hovered && !captured && !matches!(self.next_hot, Some((_, hot, _)) if hot > layer) {
+            let pressed =
+                self.hot != Some(id) && self.active.is_none() && self.input.was_pressed(button);
+            self.next_hot = Some((id, layer, if pressed { Some(button) } else { None }));
+        }
+
+        let mut interaction = Interaction {
+            hot: hovered && !captured && self.hot == Some(id),
+            ..Interaction::default()
+        };
+        if interaction.hot && self.active.is_none() && self.input.was_pressed(button) {
+            self.active = Some((id, button));
+            interaction.pressed = true;
+        }
+        if let Some((active, button)) = self.active {
+            if active == id {
+                self.active_seen = true;
+                interaction.active = true;
+                interaction.pressed |= std::mem::take(&mut self.press_pending);
+                if !self.input.is_down(button) {
+                    self.active = None;
+                    interaction.released = true;
+                    interaction.clicked = hovered;
+                }
+            }
+        }
+        interaction
+    }
+
+    /// Returns the ID of the hot widget, as determined during the previous frame.
+    pub fn hot_id(&self) -> Option<Id> {
+        self.hot
+    }
+
+    /// Returns the ID of the active widget.
+    pub fn active_id(&self) -> Option<Id> {
+        self.active.map(|(id, _)| id)
+    }
+
+    /// Deactivates the active widget, releasing the pointer capture.
+    pub fn clear_active(&mut self) {
+        self.active = None;
+        self.press_pending = false;
+    }
+
+    /// Registers the widget with the given ID as focusable, and returns whether it has focus.
+    ///
+    /// Focusable widgets must be registered during every frame, otherwise they lose focus upon the next call to
+    /// [`Ui::root`]. Tab navigation follows the order in which widgets were registered during the previous frame,
+    /// so it matches the order groups are pushed in.
+    pub fn focusable(&mut self, id: Id) -> bool {
+        self.focus.register(id);
+        self.has_focus(id)
+    }
+
+    /// Returns whether the widget with the given ID has focus.
+    pub fn has_focus(&self, id: Id) -> bool {
+        self.focus.focused() == Some(id)
+    }
+
+    /// Returns the ID of the focused widget.
+    pub fn focused_id(&self) -> Option<Id> {
+        self.focus.focused()
+    }
+
+    /// Moves focus to the widget with the given ID.
+    pub fn set_focus(&mut self, id: Id) {
+        self.focus.set_focused(Some(id));
+    }
+
+    /// Removes focus from the focused widget.
+    pub fn clear_focus(&mut self) {
+        self.focus.set_focused(None);
+    }
+}
+
+/// A guard returned by [`Ui::scoped`], which pops its group when dropped. It dereferences to the [`Ui`].
+pub struct GroupGuard<'a, T: Renderer> {
+    ui: &'a mut Ui<T>,
+    /// The stack depth with the guard's group on top.
+    depth: usize,
+}
+
+impl<'a, T: Renderer> Deref for GroupGuard<'a, T> {
+    type Target = Ui<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.ui
+    }
+}
+
+impl<'a, T: Renderer> DerefMut for GroupGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ui
+    }
+}
+
+impl<'a, T: Renderer> Drop for GroupGuard<'a, T> {
+    fn drop(&mut self) {
+        self.ui.unwind(self.depth - 1);
     }
 }
 
@@ -562,3 +1990,795 @@ impl<T: Renderer> DerefMut for Ui<T> {
         &mut self.renderer
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ui(layout: Layout) -> Ui<NoRenderer> {
+        let mut ui = Ui::new(NoRenderer);
+        ui.root((800.0, 600.0), layout);
+        ui
+    }
+
+    #[test]
+    fn flex_distributes_leftover_space() {
+        let mut ui = ui(Layout::Horizontal);
+        ui.flex(&[
+            FlexItem::fixed(200.0),
+            FlexItem::grow(1.0),
+            FlexItem::grow(3.0),
+            FlexItem::fixed(100.0),
+        ]);
+        let mut rects = Vec::new();
+        for _ in 0..4 {
+            ui.push_flex(Layout::Freeform);
+            rects.push(ui.rect());
+            ui.pop();
+        }
+        assert_eq!(rects[0], Rect::new((0.0, 0.0), (200.0, 600.0)));
+        assert_eq!(rects[1], Rect::new((200.0, 0.0), (125.0, 600.0)));
+        assert_eq!(rects[2], Rect::new((325.0, 0.0), (375.0, 600.0)));
+        assert_eq!(rects[3], Rect::new((700.0, 0.0), (100.0, 600.0)));
+    }
+
+    #[test]
+    fn flex_respects_min_and_max() {
+        let mut ui = ui(Layout::Vertical);
+        ui.flex(&[
+            FlexItem::grow(1.0).max(100.0),
+            FlexItem::grow(1.0),
+            FlexItem::grow(1.0).min(400.0),
+        ]);
+        ui.push_flex(Layout::Freeform);
+        assert_eq!(ui.size(), vector(800.0, 100.0));
+        ui.pop();
+        ui.push_flex(Layout::Freeform);
+        assert_eq!(ui.size(), vector(800.0, 100.0));
+        ui.pop();
+        ui.flex_space();
+        assert_eq!(ui.cursor(), vector(0.0, 600.0));
+    }
+
+    #[test]
+    fn flex_in_reversed_layout() {
+        let mut ui = ui(Layout::HorizontalRev);
+        ui.push((100.0, 600.0), Layout::Freeform);
+        ui.pop();
+        ui.flex(&[FlexItem::grow(1.0), FlexItem::grow(1.0)]);
+        ui.push_flex(Layout::Freeform);
+        assert_eq!(ui.rect(), Rect::new((350.0, 0.0), (350.0, 600.0)));
+        ui.pop();
+    }
+
+    #[test]
+    fn flex_with_unbounded_space() {
+        let mut ui = Ui::new(NoRenderer);
+        ui.root((f32::INFINITY, 100.0), Layout::Horizontal);
+        ui.flex(&[FlexItem::grow(1.0), FlexItem::grow(1.0).basis(50.0)]);
+        ui.push_flex(Layout::Freeform);
+        assert_eq!(ui.size(), vector(0.0, 100.0));
+        ui.pop();
+        ui.push_flex(Layout::Freeform);
+        assert_eq!(ui.size(), vector(50.0, 100.0));
+        ui.pop();
+
+        ui.push((100.0, f32::INFINITY), Layout::Grid);
+        ui.grid(
+            &[Track::Fraction(1.0)],
+            &[Track::Fixed(10.0), Track::Fraction(1.0)],
+        );
+        ui.push_cell(Layout::Freeform);
+        assert_eq!(ui.size(), vector(100.0, 10.0));
+        ui.pop();
+        ui.push_cell(Layout::Freeform);
+        assert_eq!(ui.size(), vector(100.0, 0.0));
+        ui.pop();
+        ui.pop();
+    }
+
+    #[test]
+    fn horizontal_wrap() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((100.0, 600.0), Layout::HorizontalWrap);
+        ui.set_gap(10.0, 5.0);
+        let mut positions = Vec::new();
+        for &(width, height) in &[(40.0, 10.0), (50.0, 20.0), (30.0, 10.0), (100.0, 10.0)] {
+            ui.push((width, height), Layout::Freeform);
+            positions.push(ui.position());
+            ui.pop();
+        }
+        assert_eq!(positions[0], point(0.0, 0.0));
+        assert_eq!(positions[1], point(50.0, 0.0));
+        assert_eq!(positions[2], point(0.0, 25.0));
+        assert_eq!(positions[3], point(0.0, 40.0));
+        ui.fit();
+        assert_eq!(ui.size(), vector(100.0, 50.0));
+        ui.pop();
+    }
+
+    #[test]
+    fn vertical_wrap() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((600.0, 100.0), Layout::VerticalWrap);
+        let mut positions = Vec::new();
+        for &(width, height) in &[(10.0, 60.0), (20.0, 40.0), (10.0, 10.0)] {
+            ui.push((width, height), Layout::Freeform);
+            positions.push(ui.position());
+            ui.pop();
+        }
+        assert_eq!(
+            positions,
+            [point(0.0, 0.0), point(0.0, 60.0), point(20.0, 0.0)]
+        );
+        ui.fit();
+        assert_eq!(ui.size(), vector(30.0, 100.0));
+        ui.pop();
+    }
+
+    #[test]
+    fn grid_cells_and_spans() {
+        let mut ui = ui(Layout::Grid);
+        ui.set_gap(10.0, 10.0);
+        ui.grid(
+            &[
+                Track::Fixed(100.0),
+                Track::Fraction(1.0),
+                Track::Fraction(1.0),
+            ],
+            &[Track::Fixed(50.0)],
+        );
+        let mut rects = Vec::new();
+        let mut push = |ui: &mut Ui<NoRenderer>| {
+            ui.push_cell(Layout::Freeform);
+            rects.push(ui.rect());
+            ui.pop();
+        };
+        ui.set_span(1, 2);
+        push(&mut ui);
+        push(&mut ui);
+        push(&mut ui);
+        push(&mut ui);
+        ui.set_cell(2, 3);
+        push(&mut ui);
+
+        assert_eq!(rects[0], Rect::new((0.0, 0.0), (100.0, 60.0)));
+        assert_eq!(rects[1], Rect::new((110.0, 0.0), (340.0, 50.0)));
+        assert_eq!(rects[2], Rect::new((460.0, 0.0), (340.0, 50.0)));
+        // the first column is occupied by the first cell's row span
+        assert_eq!(rects[3], Rect::new((110.0, 60.0), (340.0, 0.0)));
+        assert_eq!(rects[4], Rect::new((460.0, 80.0), (340.0, 0.0)));
+    }
+
+    #[test]
+    fn grid_auto_tracks_and_align() {
+        let mut ui = ui(Layout::Grid);
+        ui.grid(&[Track::Auto, Track::Fraction(1.0)], &[]);
+        ui.push((120.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((10.0, 30.0), Layout::Freeform);
+        assert_eq!(ui.position(), point(120.0, 0.0));
+        ui.align((Right, Top));
+        assert_eq!(ui.position(), point(790.0, 0.0));
+        ui.pop();
+        ui.push((50.0, 10.0), Layout::Freeform);
+        ui.align((Center, Top));
+        assert_eq!(ui.position(), point(35.0, 30.0));
+        ui.pop();
+        ui.fit();
+        assert_eq!(ui.size(), vector(800.0, 40.0));
+    }
+
+    #[test]
+    fn grid_follows_padding_and_gaps() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((200.0, 100.0), Layout::Grid);
+        ui.grid(&[Track::Fraction(1.0), Track::Fraction(1.0)], &[]);
+        ui.pad(10.0);
+        ui.set_gap(20.0, 0.0);
+        let mut rects = Vec::new();
+        for _ in 0..2 {
+            ui.push_cell(Layout::Freeform);
+            rects.push(ui.rect());
+            ui.pop();
+        }
+        assert_eq!(
+            rects,
+            [
+                Rect::new((10.0, 10.0), (80.0, 0.0)),
+                Rect::new((110.0, 10.0), (80.0, 0.0)),
+            ]
+        );
+        ui.pop();
+    }
+
+    #[test]
+    fn fit_reversed_layouts() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((800.0, 600.0), Layout::HorizontalRev);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((50.0, 40.0), Layout::Freeform);
+        ui.pop();
+        ui.fit();
+        assert_eq!(ui.rect(), Rect::new((650.0, 0.0), (150.0, 600.0)));
+        ui.fit_height();
+        assert_eq!(ui.rect(), Rect::new((650.0, 0.0), (150.0, 40.0)));
+        ui.pop();
+
+        ui.push((800.0, 600.0), Layout::VerticalRev);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((50.0, 40.0), Layout::Freeform);
+        ui.pop();
+        ui.fit_both();
+        assert_eq!(ui.rect(), Rect::new((0.0, 540.0), (100.0, 60.0)));
+        ui.push((10.0, 10.0), Layout::Freeform);
+        assert_eq!(ui.position(), point(0.0, 530.0));
+        ui.pop();
+        ui.pop();
+    }
+
+    #[test]
+    fn fit_cross_axis() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((800.0, 600.0), Layout::Horizontal);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((50.0, 40.0), Layout::Freeform);
+        ui.pop();
+        ui.fit_height();
+        assert_eq!(ui.size(), vector(800.0, 40.0));
+        ui.fit_width();
+        assert_eq!(ui.size(), vector(150.0, 40.0));
+        ui.pop();
+    }
+
+    #[test]
+    fn content_extent() {
+        let mut ui = ui(Layout::Freeform);
+        ui.push((800.0, 600.0), Layout::Vertical);
+        assert_eq!(ui.content_rect(), None);
+        assert_eq!(ui.content_size(), vector(0.0, 0.0));
+        ui.space(10.0);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.pop();
+        ui.push((300.0, 40.0), Layout::Freeform);
+        ui.pop();
+        assert_eq!(
+            ui.content_rect(),
+            Some(Rect::new((0.0, 10.0), (300.0, 60.0)))
+        );
+        assert_eq!(ui.content_size(), vector(300.0, 70.0));
+        ui.pop();
+
+        ui.push((800.0, 600.0), Layout::HorizontalRev);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.pop();
+        assert_eq!(ui.content_size(), vector(100.0, 20.0));
+        ui.pop();
+    }
+
+    #[test]
+    fn scroll_area() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        let mut scroll = ScrollState::new();
+        scroll.offset = vector(10.0, 30.0);
+
+        ui.push_scroll((200.0, 100.0), Layout::Horizontal, &scroll);
+        assert_eq!(ui.position(), point(-10.0, -30.0));
+        ui.push((150.0, 40.0), Layout::Freeform);
+        ui.pop();
+        ui.push((150.0, 140.0), Layout::Freeform);
+        ui.pop();
+        ui.pop_scroll(&mut scroll);
+
+        assert_eq!(ui.cursor(), vector(0.0, 100.0));
+        assert_eq!(scroll.content_size, vector(300.0, 140.0));
+        assert_eq!(scroll.viewport_size, vector(200.0, 100.0));
+        assert_eq!(scroll.max_offset(), vector(100.0, 40.0));
+        scroll.scroll_by(vector(200.0, -100.0));
+        assert_eq!(scroll.offset, vector(100.0, 0.0));
+        assert_eq!(
+            ui.renderer().commands(),
+            &[
+                DrawCommand::Push,
+                DrawCommand::Clip(Rect::new((0.0, 0.0), (200.0, 100.0))),
+                DrawCommand::Pop,
+            ]
+        );
+    }
+
+    #[test]
+    fn id_scopes() {
+        let mut ui = ui(Layout::Vertical);
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            ui.push_id(i);
+            ids.push(ui.id("button"));
+            ui.pop_id();
+        }
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[1], ids[2]);
+
+        // scopes pushed inside of a group are popped along with it
+        let outer = ui.current_id();
+        ui.push((100.0, 100.0), Layout::Freeform);
+        ui.push_id("panel");
+        assert_ne!(ui.current_id(), outer);
+        ui.pop();
+        assert_eq!(ui.current_id(), outer);
+
+        // IDs are stable across frames
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui.push_id(0);
+        assert_eq!(ui.id("button"), ids[0]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "duplicate widget ID")]
+    fn duplicate_ids_panic() {
+        let mut ui = ui(Layout::Vertical);
+        ui.id("button");
+        ui.id("button");
+    }
+
+    #[test]
+    #[should_panic(expected = "without a matching push_id()")]
+    fn pop_id_outside_of_group_panics() {
+        let mut ui = ui(Layout::Vertical);
+        ui.push_id("outer");
+        ui.push((100.0, 100.0), Layout::Freeform);
+        ui.pop_id();
+    }
+
+    #[test]
+    fn state_is_collected_when_untouched() {
+        let mut ui = ui(Layout::Vertical);
+        let a = ui.id("a");
+        let b = ui.id("b");
+        *ui.state::<u32>(a) = 1;
+        *ui.state::<u32>(b) = 2;
+        // the same ID can hold values of different types
+        ui.state::<bool>(a);
+
+        ui.root((800.0, 600.0), Layout::Vertical);
+        assert_eq!(*ui.state::<u32>(a), 1);
+        assert_eq!(ui.state.len(), 3);
+
+        ui.root((800.0, 600.0), Layout::Vertical);
+        assert_eq!(ui.state.len(), 1);
+        assert_eq!(*ui.state::<u32>(a), 1);
+        assert_eq!(*ui.state::<u32>(b), 0);
+    }
+
+    /// Runs a frame with two overlapping 100×100 widgets at (0, 0) and (50, 0), returning their interactions.
+    fn overlapping_widgets(ui: &mut Ui<NoRenderer>, input: Input) -> [Interaction; 2] {
+        ui.root((800.0, 600.0), Layout::Freeform);
+        ui.set_input(input);
+        let mut interactions = [Interaction::default(); 2];
+        for (i, interaction) in interactions.iter_mut().enumerate() {
+            ui.set_cursor(vector(i as f32 * 50.0, 0.0));
+            ui.push((100.0, 100.0), Layout::Freeform);
+            let id = ui.id(i);
+            *interaction = ui.interact(id, MouseButton::Left);
+            ui.pop();
+        }
+        interactions
+    }
+
+    #[test]
+    fn hot_and_active_with_capture() {
+        let mut ui = Ui::new(NoRenderer);
+        let mut input = Input::new();
+        input.set_pointer(Some(point(75.0, 50.0)));
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        // nothing was hot during the previous frame, and the topmost widget isn't known until both are registered
+        assert!(!a.hot && !b.hot);
+
+        // the widget on top wins
+        input.press(MouseButton::Left);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !a.pressed);
+        assert!(b.hot && b.pressed && b.active);
+        input.next_frame();
+
+        // the active widget captures the pointer
+        input.set_pointer(Some(point(25.0, 50.0)));
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !a.active);
+        assert!(!b.hot && b.active);
+
+        input.release(MouseButton::Left);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot);
+        assert!(b.active && b.released && !b.clicked);
+        assert_eq!(ui.active_id(), None);
+        input.next_frame();
+
+        // b captured the pointer during the previous frame, so a only becomes hot during the next one
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !b.hot);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(a.hot && !b.hot);
+    }
+
+    #[test]
+    fn press_without_hot_widget_activates_topmost() {
+        let mut ui = Ui::new(NoRenderer);
+        let mut input = Input::new();
+        input.set_pointer(Some(point(75.0, 50.0)));
+        input.press(MouseButton::Left);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        // the topmost widget isn't known until both are registered, so the press is deferred to the next frame
+        assert!(!a.hot && !a.pressed && !a.active);
+        assert!(!b.hot && !b.pressed && !b.active);
+        input.next_frame();
+
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !a.pressed && !a.active);
+        assert!(b.hot && b.pressed && b.active);
+
+        let [_, b] = overlapping_widgets(&mut ui, input);
+        assert!(b.active && !b.pressed);
+    }
+
+    #[test]
+    fn press_after_moving_to_another_widget() {
+        let mut ui = Ui::new(NoRenderer);
+        let mut input = Input::new();
+        input.set_pointer(Some(point(25.0, 50.0)));
+        overlapping_widgets(&mut ui, input);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(a.hot && !b.hot);
+
+        // the pointer moves from a to b and presses during the same frame
+        input.set_pointer(Some(point(125.0, 50.0)));
+        input.press(MouseButton::Left);
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !a.active);
+        assert!(!b.hot && !b.pressed && !b.active);
+        input.next_frame();
+
+        let [a, b] = overlapping_widgets(&mut ui, input);
+        assert!(!a.hot && !a.active);
+        assert!(b.hot && b.pressed && b.active);
+    }
+
+    #[test]
+    fn active_widget_is_dropped_when_not_registered() {
+        let mut ui = Ui::new(NoRenderer);
+        let mut input = Input::new();
+        input.set_pointer(Some(point(10.0, 10.0)));
+        input.press(MouseButton::Left);
+        overlapping_widgets(&mut ui, input);
+        overlapping_widgets(&mut ui, input);
+        assert!(ui.active_id().is_some());
+
+        ui.root((800.0, 600.0), Layout::Freeform);
+        ui.root((800.0, 600.0), Layout::Freeform);
+        assert_eq!(ui.active_id(), None);
+    }
+
+    /// Runs a frame with three focusable widgets, returning the index of the focused one.
+    fn focus_frame(ui: &mut Ui<NoRenderer>, input: Input) -> Option<usize> {
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui.set_input(input);
+        let mut focused = None;
+        for i in 0..3 {
+            ui.push((100.0, 20.0), Layout::Freeform);
+            let id = ui.id(i);
+            if ui.focusable(id) {
+                focused = Some(i);
+            }
+            ui.pop();
+        }
+        focused
+    }
+
+    #[test]
+    fn tab_navigation() {
+        let mut ui = Ui::new(NoRenderer);
+        let mut tab = Input::new();
+        tab.press_key(Key::Tab);
+        let mut shift_tab = tab;
+        shift_tab.set_modifiers(Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        });
+
+        assert_eq!(focus_frame(&mut ui, Input::new()), None);
+        assert_eq!(focus_frame(&mut ui, tab), Some(0));
+        assert_eq!(focus_frame(&mut ui, tab), Some(1));
+        assert_eq!(focus_frame(&mut ui, Input::new()), Some(1));
+        assert_eq!(focus_frame(&mut ui, tab), Some(2));
+        assert_eq!(focus_frame(&mut ui, tab), Some(0));
+        assert_eq!(focus_frame(&mut ui, shift_tab), Some(2));
+
+        ui.set_focus(Id::ROOT.with(1usize));
+        assert_eq!(focus_frame(&mut ui, Input::new()), Some(1));
+
+        // key repeats move focus once per press
+        let mut double_tab = tab;
+        double_tab.press_key(Key::Tab);
+        assert_eq!(double_tab.key_presses(Key::Tab), 2);
+        assert_eq!(focus_frame(&mut ui, double_tab), Some(0));
+
+        // focus is dropped when the widget disappears
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui.root((800.0, 600.0), Layout::Vertical);
+        assert_eq!(ui.focused_id(), None);
+    }
+
+    /// Runs a frame with a dropdown button that opens a 100×100 overlay, followed by a 200×20 sibling that the
+    /// overlay partially covers. Returns whether the overlay and the sibling are hovered.
+    fn dropdown_frame(ui: &mut Ui<RecordingRenderer<NoRenderer>>, input: Input) -> (bool, bool) {
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui.set_input(input);
+        ui.push((100.0, 20.0), Layout::Freeform);
+        ui.fill(Color::BLACK);
+        ui.push((100.0, 100.0), Layout::Freeform);
+        ui.set_layer(Layer::Overlay);
+        ui.fill(Color::WHITE);
+        let overlay = ui.hovered();
+        ui.pop();
+        ui.pop();
+        ui.push((200.0, 20.0), Layout::Freeform);
+        ui.fill(Color::BLACK);
+        let sibling = ui.hovered();
+        ui.pop();
+        ui.flush_layers();
+        (overlay, sibling)
+    }
+
+    #[test]
+    fn overlays_are_drawn_last() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        dropdown_frame(&mut ui, Input::new());
+        let colors: Vec<_> = ui
+            .renderer()
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Fill { color, .. } => Some(*color),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(colors, [Color::BLACK, Color::BLACK, Color::WHITE]);
+        assert_eq!(
+            ui.renderer().commands()[2..],
+            [DrawCommand::Fill {
+                rect: Rect::new((0.0, 0.0), (100.0, 100.0)),
+                color: Color::WHITE,
+                radius: 0.0,
+            },]
+        );
+    }
+
+    #[test]
+    fn overlays_block_input() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        let mut input = Input::new();
+        input.set_pointer(Some(point(50.0, 30.0)));
+        // the overlay's rectangle is only known after the first frame
+        assert_eq!(dropdown_frame(&mut ui, input), (true, true));
+        assert_eq!(dropdown_frame(&mut ui, input), (true, false));
+        input.set_pointer(Some(point(150.0, 30.0)));
+        assert_eq!(dropdown_frame(&mut ui, input), (false, true));
+    }
+
+    /// A renderer whose font can't be cloned, and that counts the text it draws.
+    struct UniqueFontRenderer(usize);
+
+    struct UniqueFont;
+
+    impl Renderer for UniqueFontRenderer {
+        type Font = UniqueFont;
+        type Image = UniqueFont;
+
+        fn push(&mut self) {}
+        fn pop(&mut self) {}
+        fn translate(&mut self, _: Vector) {}
+        fn clip(&mut self, _: Rect) {}
+        fn fill(&mut self, _: Rect, _: Color, _: f32) {}
+        fn outline(&mut self, _: Rect, _: Color, _: f32, _: f32) {}
+        fn line(&mut self, _: Point, _: Point, _: Color, _: LineCap, _: f32) {}
+
+        fn text(&mut self, _: Rect, _: &Self::Font, _: &str, _: Color, _: Alignment) -> f32 {
+            self.0 += 1;
+            0.0
+        }
+    }
+
+    #[test]
+    fn drawing_doesnt_require_clone_fonts() {
+        let mut ui = Ui::new(UniqueFontRenderer(0));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        let mut scroll = ScrollState::new();
+        ui.push_scroll((100.0, 100.0), Layout::Vertical, &scroll);
+        ui.fill(Color::BLACK);
+        ui.text(&UniqueFont, "hello", Color::WHITE, CENTER);
+        ui.pop_scroll(&mut scroll);
+        assert_eq!(ui.renderer().0, 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn finish_reports_unbalanced_pushes() {
+        let mut ui = ui(Layout::Vertical);
+        ui.push((100.0, 100.0), Layout::Vertical);
+        ui.pop();
+        assert_eq!(ui.finish(), Ok(()));
+
+        ui.push((100.0, 100.0), Layout::Vertical);
+        let line = line!() - 1;
+        ui.push_scroll((100.0, 100.0), Layout::Vertical, &ScrollState::new());
+        let error = ui.finish().unwrap_err();
+        let locations: Vec<_> = error.locations().iter().map(|l| l.unwrap()).collect();
+        assert_eq!(locations.len(), 3);
+        assert_eq!(locations[0].file(), file!());
+        assert_eq!(locations[0].line(), line);
+        // both groups of the scroll area point to the push_scroll() call
+        assert_eq!(locations[1].line(), line + 2);
+        assert_eq!(locations[2].line(), line + 2);
+        assert!(error
+            .to_string()
+            .contains(&format!("unmatched push at {}:{}", file!(), line)));
+    }
+
+    #[test]
+    fn images_are_fit_into_groups() {
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        ui.push((100.0, 50.0), Layout::Freeform);
+        let image = NoRendererImage {
+            size: vector(20.0, 10.0),
+        };
+        ui.image(&image, ImageFit::Contain, Color::WHITE);
+        ui.image(&image, ImageFit::Center, Color::BLACK);
+        ui.pop();
+        assert_eq!(
+            ui.renderer().commands(),
+            [
+                DrawCommand::Image {
+                    rect: Rect::new((0.0, 0.0), (100.0, 50.0)),
+                    image,
+                    source: Rect::new((0.0, 0.0), (20.0, 10.0)),
+                    tint: Color::WHITE,
+                },
+                DrawCommand::Image {
+                    rect: Rect::new((40.0, 20.0), (20.0, 10.0)),
+                    image,
+                    source: Rect::new((0.0, 0.0), (20.0, 10.0)),
+                    tint: Color::BLACK,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn guards_unwind_the_stack() {
+        fn widget(ui: &mut Ui<RecordingRenderer<NoRenderer>>) -> Result<(), ()> {
+            ui.group((200.0, 100.0), Layout::Vertical, |ui| {
+                ui.push_scroll((200.0, 50.0), Layout::Vertical, &ScrollState::new());
+                ui.push((200.0, 20.0), Layout::Freeform);
+                Err(())
+            })
+        }
+
+        let mut ui = Ui::new(RecordingRenderer::new(NoRenderer));
+        ui.root((800.0, 600.0), Layout::Vertical);
+        assert_eq!(widget(&mut ui), Err(()));
+        assert_eq!(ui.cursor(), vector(0.0, 100.0));
+        assert_eq!(ui.finish(), Ok(()));
+        // the scroll area's renderer state was popped, too
+        assert_eq!(ui.renderer().commands().last(), Some(&DrawCommand::Pop));
+
+        {
+            let mut guard = ui.scoped((100.0, 100.0), Layout::Freeform);
+            guard.push((10.0, 10.0), Layout::Freeform);
+            guard.pop();
+            assert_eq!(guard.size(), vector(100.0, 100.0));
+        }
+        assert_eq!(ui.cursor(), vector(0.0, 200.0));
+    }
+
+    #[test]
+    fn try_methods_report_errors() {
+        let mut ui = Ui::new(NoRenderer);
+        assert_eq!(ui.try_rect(), Err(UiError::EmptyStack));
+        assert_eq!(
+            ui.try_push((10.0, 10.0), Layout::Freeform),
+            Err(UiError::EmptyStack)
+        );
+        assert_eq!(ui.try_align((Center, Middle)), Err(UiError::EmptyStack));
+
+        ui.root((800.0, 600.0), Layout::Freeform);
+        assert_eq!(ui.try_pop(), Err(UiError::MissingParent));
+        assert_eq!(ui.try_align((Center, Middle)), Err(UiError::MissingParent));
+        assert_eq!(
+            ui.try_space(8.0),
+            Err(UiError::InvalidLayout {
+                operation: "space",
+                layout: Layout::Freeform,
+            })
+        );
+        assert_eq!(
+            ui.try_flex(&[FlexItem::grow(1.0)]),
+            Err(UiError::InvalidLayout {
+                operation: "flex",
+                layout: Layout::Freeform,
+            })
+        );
+        assert_eq!(
+            ui.try_push_flex(Layout::Freeform),
+            Err(UiError::FlexPlanExhausted)
+        );
+        assert_eq!(
+            ui.try_push_cell(Layout::Freeform),
+            Err(UiError::MissingGrid)
+        );
+        assert_eq!(
+            ui.try_pop_scroll(&mut ScrollState::new()),
+            Err(UiError::NotScrollArea)
+        );
+
+        assert_eq!(ui.try_push((10.0, 10.0), Layout::Freeform), Ok(()));
+        assert_eq!(ui.try_align((Right, Bottom)), Ok(()));
+        assert_eq!(ui.try_rect(), Ok(Rect::new((790.0, 590.0), (10.0, 10.0))));
+        assert_eq!(ui.try_pop(), Ok(()));
+        assert_eq!(ui.finish(), Ok(()));
+
+        let mut scroll = ScrollState::new();
+        ui.push_scroll((100.0, 100.0), Layout::Vertical, &scroll);
+        assert_eq!(ui.try_pop(), Err(UiError::ScrollAreaNotPopped));
+        assert_eq!(ui.try_pop_scroll(&mut scroll), Ok(()));
+        assert_eq!(ui.finish(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "pop() without a matching push()")]
+    fn popping_root_panics() {
+        let mut ui = ui(Layout::Vertical);
+        ui.pop();
+    }
+
+    #[test]
+    #[should_panic(expected = "must be popped with pop_scroll()")]
+    fn popping_scroll_area_panics() {
+        let mut ui = ui(Layout::Vertical);
+        ui.push_scroll((100.0, 100.0), Layout::Vertical, &ScrollState::new());
+        ui.pop();
+    }
+
+    #[test]
+    fn hit_testing_respects_clip() {
+        let mut ui = ui(Layout::Vertical);
+        let mut input = Input::new();
+        input.set_pointer(Some(point(50.0, 150.0)));
+        input.press(MouseButton::Left);
+        ui.set_input(input);
+
+        let scroll = ScrollState::new();
+        ui.push_scroll((200.0, 100.0), Layout::Vertical, &scroll);
+        ui.push((200.0, 100.0), Layout::Freeform);
+        assert!(!ui.hovered());
+        ui.pop();
+        ui.push((200.0, 100.0), Layout::Freeform);
+        // this group is below the viewport, so it's clipped out
+        assert!(!ui.hovered());
+        assert!(!ui.pressed(MouseButton::Left));
+        ui.pop();
+        ui.pop_scroll(&mut ScrollState::new());
+
+        ui.push((200.0, 100.0), Layout::Freeform);
+        assert!(ui.hovered());
+        assert!(ui.pressed(MouseButton::Left));
+        assert!(ui.held(MouseButton::Left));
+        assert!(!ui.clicked(MouseButton::Left));
+        assert!(!ui.pressed(MouseButton::Right));
+        ui.pop();
+    }
+}